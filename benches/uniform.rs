@@ -0,0 +1,50 @@
+extern crate term_grid;
+use term_grid::{Grid, GridOptions, Direction, Cell};
+
+use std::time::{Duration, Instant};
+
+// Compares laying out a grid of equal-width cells, which can skip measuring
+// each column, with one where a single cell is narrower than the rest and
+// every column has to be measured. Run with `cargo bench`.
+
+const CELLS: usize = 20_000;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    let uniform = grid(false);
+    let mixed = grid(true);
+
+    let uniform_time = time(&uniform);
+    let mixed_time = time(&mixed);
+
+    println!("uniform widths:  {:?} per layout", uniform_time / ROUNDS);
+    println!("measured widths: {:?} per layout", mixed_time / ROUNDS);
+}
+
+fn grid(one_narrow_cell: bool) -> Grid {
+    let mut grid = Grid::new(GridOptions {
+        direction:  Direction::TopToBottom,
+        ..GridOptions::default()
+    });
+
+    for i in 0 .. CELLS {
+        grid.add(Cell::from(format!("{:08}", i)));
+    }
+
+    if one_narrow_cell {
+        grid.add(Cell::from("x"));
+    }
+    else {
+        grid.add(Cell::from("xxxxxxxx"));
+    }
+
+    grid
+}
+
+fn time(grid: &Grid) -> Duration {
+    let start = Instant::now();
+    for _ in 0 .. ROUNDS {
+        assert!(grid.fit_into_width(200).is_some());
+    }
+    start.elapsed()
+}
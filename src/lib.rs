@@ -9,6 +9,8 @@
 
 #![deny(unsafe_code)]
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
 
 //! This library arranges textual data in a grid format suitable for
 //! fixed-width fonts, using an algorithm to minimise the amount of space
@@ -20,6 +22,7 @@
 //! let mut grid = Grid::new(GridOptions {
 //!     filling:    Filling::Spaces(1),
 //!     direction:  Direction::LeftToRight,
+//!     ..GridOptions::default()
 //! });
 //!
 //! for s in &["one", "two", "three", "four", "five", "six", "seven",
@@ -45,19 +48,19 @@
 //! To add data to a grid, first create a new [`Grid`] value, and then add
 //! cells to them with the `add` function.
 //!
-//! There are two options that must be specified in the [`GridOptions`] value
-//! that dictate how the grid is formatted:
+//! There are two main options in the [`GridOptions`] value that dictate how
+//! the grid is formatted; the rest have defaults:
 //!
 //! - `filling`: what to put in between two columns — either a number of
-//!    spaces, or a text string;
+//!   spaces, or a text string;
 //! - `direction`, which specifies whether the cells should go along
-//!    rows, or columns:
+//!   rows, or columns:
 //!     - `Direction::LeftToRight` starts them in the top left and
-//!        moves *rightwards*, going to the start of a new row after reaching the
-//!        final column;
+//!       moves *rightwards*, going to the start of a new row after reaching the
+//!       final column;
 //!     - `Direction::TopToBottom` starts them in the top left and moves
-//!        *downwards*, going to the top of a new column after reaching the final
-//!        row.
+//!       *downwards*, going to the top of a new column after reaching the final
+//!       row.
 //!
 //!
 //! ## Displaying a grid
@@ -94,6 +97,14 @@
 //! `Cell` values are public, meaning you can construct your own instances as
 //! necessary.
 //!
+//!
+//! ## `no_std` support
+//!
+//! The crate only needs an allocator, not the whole standard library. Turning
+//! off the default `std` feature builds it with `#![no_std]` on top of the
+//! `alloc` crate; rendering works through `core::fmt` as usual, so
+//! `to_string()` and `write!` into any `fmt::Write` are still available.
+//!
 //! [`Cell`]: ./struct.Cell.html
 //! [`Display`]: ./struct.Display.html
 //! [`Grid`]: ./struct.Grid.html
@@ -102,17 +113,41 @@
 //! [`GridOptions`]: ./struct.GridOptions.html
 
 
-use std::cmp::max;
-use std::fmt;
-use std::iter::repeat;
+#[cfg(feature = "std")]
+extern crate core;
+#[macro_use]
+extern crate alloc;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::cmp::{max, Ordering};
+use core::convert::TryFrom;
+use core::fmt;
+use core::mem;
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
+use std::io;
 
 extern crate unicode_width;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[cfg(feature = "normalization")]
+extern crate unicode_normalization;
+#[cfg(feature = "normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+#[cfg(feature = "terminal")]
+extern crate terminal_size;
+#[cfg(feature = "terminal")]
+use terminal_size::terminal_size;
 
 
 /// Alignment indicate on which side the content should stick if some filling
 /// is required.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alignment {
 
     /// The content will stick to the left.
@@ -120,6 +155,47 @@ pub enum Alignment {
 
     /// The content will stick to the right.
     Right,
+
+    /// The content will stick to the right, with the space to its left
+    /// filled with zeroes rather than spaces — useful for IDs and codes.
+    RightZeroPad,
+
+    /// The content will be shifted so that the first occurrence of this
+    /// string in it lines up with the same string in the other cells in
+    /// its column, such as the colons in `key: value` pairs. A cell that
+    /// lacks the string lines up as though it all came before it.
+    On(String),
+
+    /// The content will be in the middle, with the padding split between
+    /// its two sides. When the padding can’t be split evenly, the extra
+    /// space goes on the given side.
+    Center {
+        /// Which side gets the odd column of padding.
+        extra_on: Side,
+    },
+}
+
+/// One side of a cell, for alignments that need to pick one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+
+    /// The left-hand side.
+    Left,
+
+    /// The right-hand side.
+    Right,
+}
+
+impl Side {
+
+    /// Returns how much of the given padding goes before the content of a
+    /// centred cell, when any extra goes on this side.
+    fn centred_lead(self, padding: Width) -> Width {
+        match self {
+            Side::Left   => padding - padding / 2,
+            Side::Right  => padding / 2,
+        }
+    }
 }
 
 
@@ -128,7 +204,7 @@ pub enum Alignment {
 /// The easiest way to create a Cell is just by using `string.into()`, which
 /// uses the **unicode width** of the string (see the `unicode_width` crate).
 /// However, the fields are public, if you wish to provide your own length.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub struct Cell {
 
     /// The string to display when this cell gets rendered.
@@ -139,6 +215,154 @@ pub struct Cell {
 
     /// The side (left/right) to align the content if some filling is required.
     pub alignment: Alignment,
+
+    /// Whether this cell may be cut short to let the grid fit more columns
+    /// into the width. Flexible cells that don’t fit are truncated with an
+    /// ellipsis when rendered, though never to narrower than the widest
+    /// cell that isn’t flexible.
+    pub flexible: bool,
+
+    /// The number of columns this cell takes up, such as for a header
+    /// that stretches across a section of the grid. The width of the cell
+    /// is spread over the columns and the fillings between them. Cells
+    /// only span columns when the grid is written left to right, and a
+    /// cell that would run off the end of a row starts the next one.
+    pub colspan: usize,
+
+    /// The character to pad this cell with instead of spaces, such as `.`
+    /// for the dotted leaders in a table of contents. A cell with a leader
+    /// always gets padded to the width of its column.
+    pub leader: Option<char>,
+}
+
+/// Parses an alignment from its name — `"left"`, `"right"`,
+/// `"rightzeropad"`, or `"center"` — ignoring case. Centred cells parsed
+/// this way put any odd column of padding on the right.
+impl FromStr for Alignment {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match &*input.to_ascii_lowercase() {
+            "left"          => Ok(Alignment::Left),
+            "right"         => Ok(Alignment::Right),
+            "rightzeropad"  => Ok(Alignment::RightZeroPad),
+            "center"        => Ok(Alignment::Center { extra_on: Side::Right }),
+            _               => Err(ParseError::Unrecognised(input.into())),
+        }
+    }
+}
+
+
+impl Cell {
+
+    /// Returns how many columns this cell spans in a layout with the given
+    /// number of columns, which is never fewer than one or more than all.
+    fn span(&self, num_columns: usize) -> usize {
+        max(1, self.colspan.min(num_columns))
+    }
+
+    /// Returns the width of the part of this cell that comes before the
+    /// first occurrence of the given separator, or the whole width if the
+    /// separator isn’t in it.
+    fn prefix_width(&self, separator: &str) -> Width {
+        match self.contents.find(separator) {
+            Some(position)  => UnicodeWidthStr::width(&self.contents[.. position]).min(self.width),
+            None            => self.width,
+        }
+    }
+
+    /// Creates a cell that displays the given text as a terminal hyperlink
+    /// to the given URL, using the OSC 8 escape sequence.
+    ///
+    /// The width is measured from the visible text alone, so the escape
+    /// codes around it don’t throw off the alignment.
+    pub fn from_hyperlinked(text: &str, url: &str) -> Self {
+        Self {
+            contents: format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text),
+            width: UnicodeWidthStr::width(text),
+            alignment: Alignment::Left,
+            flexible: false,
+            colspan: 1,
+            leader: None,
+        }
+    }
+
+    /// Creates a cell whose width is the number of characters in the given
+    /// text, rather than its Unicode width.
+    ///
+    /// This is quicker to work out, and matches tools that count characters,
+    /// but it only lines up properly when every character takes up one
+    /// column, so it’s best kept for text without any wide or zero-width
+    /// characters in it.
+    pub fn from_char_count(text: &str) -> Self {
+        Self {
+            contents: text.into(),
+            width: text.chars().count(),
+            alignment: Alignment::Left,
+            flexible: false,
+            colspan: 1,
+            leader: None,
+        }
+    }
+
+    /// Creates a right-aligned cell containing the given number, with its
+    /// digits split into groups of three by the given separator, such as
+    /// `1,234,567`.
+    pub fn from_number(n: i64, group_separator: char) -> Self {
+        let digits = format!("{}", n);
+        let (sign, digits) = if n < 0 { digits.split_at(1) } else { ("", &digits[..]) };
+
+        let mut contents = String::from(sign);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                contents.push(group_separator);
+            }
+            contents.push(digit);
+        }
+
+        Self {
+            width: UnicodeWidthStr::width(&*contents),
+            contents,
+            alignment: Alignment::Right,
+            flexible: false,
+            colspan: 1,
+            leader: None,
+        }
+    }
+
+    /// Creates a cell from the given string after normalising it to NFC, so
+    /// that text with decomposed accents gets measured and rendered the same
+    /// as text with precomposed ones.
+    ///
+    /// This is only available with the `normalization` feature.
+    #[cfg(feature = "normalization")]
+    pub fn from_normalized(string: &str) -> Self {
+        Self::from(string.nfc().collect::<String>())
+    }
+
+    /// Returns this cell with its contents already padded out to the given
+    /// width, using the given alignment, so the grid won’t need to pad it.
+    ///
+    /// A cell that’s already at least as wide is left as it is, apart from
+    /// its alignment. Cells aligned on a separator get padded on the right.
+    pub fn padded_to(mut self, width: Width, alignment: Alignment) -> Cell {
+        let extra = width.saturating_sub(self.width);
+        if extra > 0 {
+            self.contents = match alignment {
+                Alignment::Right         => spaces(extra) + &self.contents,
+                Alignment::RightZeroPad  => padding('0', extra) + &self.contents,
+                Alignment::Center { extra_on } => {
+                    let lead = extra_on.centred_lead(extra);
+                    spaces(lead) + &self.contents + &spaces(extra - lead)
+                },
+                _                        => self.contents + &spaces(extra),
+            };
+            self.width = width;
+        }
+
+        self.alignment = alignment;
+        self
+    }
 }
 
 impl From<String> for Cell {
@@ -147,6 +371,9 @@ impl From<String> for Cell {
             width: UnicodeWidthStr::width(&*string),
             contents: string,
             alignment: Alignment::Left,
+            flexible: false,
+            colspan: 1,
+            leader: None,
         }
     }
 }
@@ -154,16 +381,19 @@ impl From<String> for Cell {
 impl<'a> From<&'a str> for Cell {
     fn from(string: &'a str) -> Self {
         Self {
-            width: UnicodeWidthStr::width(&*string),
+            width: UnicodeWidthStr::width(string),
             contents: string.into(),
             alignment: Alignment::Left,
+            flexible: false,
+            colspan: 1,
+            leader: None,
         }
     }
 }
 
 
 /// Direction cells should be written in — either across, or downwards.
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
 pub enum Direction {
 
     /// Starts at the top left and moves rightwards, going back to the first
@@ -172,9 +402,28 @@ pub enum Direction {
 
     /// Starts at the top left and moves downwards, going back to the first
     /// row for a new column, like how `ls` lists files by default.
+    ///
+    /// Every column but the last is filled to the full number of rows, so
+    /// only the final column is ever short: ten cells in three columns get
+    /// split four, four, and two.
     TopToBottom,
 }
 
+/// Parses a direction from its name, ignoring case: `"ltr"`,
+/// `"lefttoright"` or `"across"` for `LeftToRight`, and `"ttb"`,
+/// `"toptobottom"` or `"down"` for `TopToBottom`.
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match &*input.to_ascii_lowercase() {
+            "ltr" | "lefttoright" | "across"  => Ok(Direction::LeftToRight),
+            "ttb" | "toptobottom" | "down"    => Ok(Direction::TopToBottom),
+            _                                 => Err(ParseError::Unrecognised(input.into())),
+        }
+    }
+}
+
 
 /// The width of a cell, in columns.
 pub type Width = usize;
@@ -182,7 +431,7 @@ pub type Width = usize;
 
 /// The text to put in between each pair of columns.
 /// This does not include any spaces used when aligning cells.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug)]
 pub enum Filling {
 
     /// A certain number of spaces should be used as the separator.
@@ -190,21 +439,129 @@ pub enum Filling {
 
     /// An arbitrary string.
     /// `"|"` is a common choice.
+    ///
+    /// The string gets written out as-is, so a control character such as
+    /// a newline or a tab in here will garble the grid. Use `Filling::text`
+    /// to check for these.
     Text(String),
 }
 
 impl Filling {
+
+    /// Creates a text filling from the given string, checking that it
+    /// contains no control characters.
+    pub fn text(text: &str) -> Result<Self, FillingError> {
+        match text.chars().find(|c| c.is_control()) {
+            Some(c)  => Err(FillingError::ControlCharacter(c)),
+            None     => Ok(Filling::Text(text.into())),
+        }
+    }
+
     fn width(&self) -> Width {
         match *self {
             Filling::Spaces(w)   => w,
             Filling::Text(ref t) => UnicodeWidthStr::width(&t[..]),
         }
     }
+
+    /// The number of bytes this filling takes up when written out.
+    fn byte_len(&self) -> usize {
+        match *self {
+            Filling::Spaces(w)   => w,
+            Filling::Text(ref t) => t.len(),
+        }
+    }
+}
+
+/// The error returned when a filling cannot be used to separate columns.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum FillingError {
+
+    /// The filling text contains a control character, such as a newline.
+    ControlCharacter(char),
+}
+
+impl fmt::Display for FillingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            FillingError::ControlCharacter(c) => {
+                write!(f, "filling contains the control character {:?}", c)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FillingError {}
+
+/// Parses a filling from a specification such as `"spaces:2"` or `"text:|"`,
+/// which is handy for taking the filling from a command-line flag or a
+/// configuration file.
+///
+/// A bare number, such as `"2"`, is taken as a number of spaces. Text
+/// fillings are checked for control characters as with `Filling::text`.
+impl FromStr for Filling {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Ok(n) = input.parse() {
+            return Ok(Filling::Spaces(n));
+        }
+
+        if let Some(count) = strip_prefix(input, "spaces:") {
+            return count.parse()
+                        .map(Filling::Spaces)
+                        .map_err(|_| ParseError::InvalidNumber(count.into()));
+        }
+
+        if let Some(text) = strip_prefix(input, "text:") {
+            return Filling::text(text).map_err(ParseError::InvalidFilling);
+        }
+
+        Err(ParseError::Unrecognised(input.into()))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Filling {
+    type Error = ParseError;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        input.parse()
+    }
+}
+
+
+/// The error returned when one of the grid’s options can’t be parsed from
+/// a string.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ParseError {
+
+    /// The string isn’t in any of the accepted forms.
+    Unrecognised(String),
+
+    /// The string should have contained a number, but this isn’t one.
+    InvalidNumber(String),
+
+    /// The string described a text filling that can’t be used.
+    InvalidFilling(FillingError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParseError::Unrecognised(ref input)   => write!(f, "unrecognised value {:?}", input),
+            ParseError::InvalidNumber(ref input)  => write!(f, "invalid number {:?}", input),
+            ParseError::InvalidFilling(ref e)     => write!(f, "invalid filling: {}", e),
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 /// The user-assignable options for a grid view that should be passed to
 /// [`Grid::new()`](struct.Grid.html#method.new).
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug)]
 pub struct GridOptions {
 
     /// The direction that the cells should be written in — either
@@ -213,11 +570,261 @@ pub struct GridOptions {
 
     /// The number of spaces to put in between each column of cells.
     pub filling: Filling,
+
+    /// The codes to wrap around alternating rows when rendering, if any.
+    pub row_styles: Option<RowStyles>,
+
+    /// How `fit_into_width` should choose between layouts that all fit.
+    pub packing: Packing,
+
+    /// A line to draw after every so many rows to split them into groups,
+    /// as the number of rows per group and the text to repeat across the
+    /// width of the grid.
+    pub row_separator: Option<(usize, String)>,
+
+    /// Whether to pad the cells in the final column out to the column’s
+    /// width. By default, lines are never given trailing whitespace.
+    pub pad_final_column: bool,
+
+    /// The number of spaces to indent every line of the grid by.
+    ///
+    /// The indent counts towards `Display::width()`, but the `fit_into_`
+    /// methods lay out the cells alone, so subtract it from the width you
+    /// pass them.
+    pub indent: Width,
+
+    /// Labels to print in a row above the cells, one for each column from
+    /// left to right. Each column is made at least as wide as its label.
+    /// Labels for columns that the layout doesn’t end up using are left
+    /// out, as are the labels of a grid with no cells.
+    pub header: Option<Vec<String>>,
+
+    /// Cells to print in a row below the rest, one for each column from
+    /// left to right, such as for totals. As with the header, each column
+    /// is made at least as wide as its footer cell, and footer cells for
+    /// columns that the layout doesn’t end up using are left out.
+    pub footer: Option<Vec<Cell>>,
+
+    /// The most that each column may be widened to, from left to right.
+    /// Cells that are too wide for their column get cut short or wrapped
+    /// onto more lines, depending on `wrap_mode`. Columns past the end of
+    /// the list have no limit, and a limit of zero counts as one.
+    pub column_max_widths: Option<Vec<Width>>,
+
+    /// What to do with cells that are too wide for their column.
+    pub wrap_mode: WrapMode,
+
+    /// The alignment to give cells that get added as strings, with
+    /// `Grid::add_str` or `Grid::add_cached`. Cells added with `Grid::add`
+    /// always keep the alignment they already have.
+    pub default_alignment: Alignment,
+
+    /// How to cut short cells that are too wide for their column, when
+    /// `wrap_mode` is `WrapMode::Truncate`.
+    pub truncation: Truncation,
+
+    /// How to choose between the layouts that fit, when packing with
+    /// `Packing::MaxColumns`.
+    pub tie_break: TieBreak,
+
+    /// Write a blank line after every this many rows, to break long output
+    /// up into pages. There’s never a blank line after the last row.
+    pub page_break_every: Option<usize>,
+
+    /// The least amount of space to leave in between each pair of columns,
+    /// counting the filling. When the filling is narrower than this, it
+    /// gets spaces added either side of it to make up the difference, with
+    /// any odd one going after it.
+    pub min_gap: Width,
+
+    /// Which side of a text filling the padding of left-aligned cells goes
+    /// on. This makes no difference to fillings made of spaces.
+    pub text_align: TextFillAlign,
+
+    /// Whether to number the rows, counting from 1, in a column to the left
+    /// of the cells. The numbers are right-aligned, with a space after them.
+    ///
+    /// As with `indent`, the numbers count towards `Display::width()`, but
+    /// the `fit_into_` methods lay out the cells alone.
+    pub row_numbers: bool,
+
+    /// Line the columns up with tabs instead of padding them with spaces,
+    /// for viewers that expand tabs, assuming a tab stop every this many
+    /// columns (a tab stop of zero counts as one).
+    ///
+    /// Each cell is followed by as many tabs as it takes to reach the tab
+    /// stop where the next column starts, which is the first one past the
+    /// end of the widest cell in the column, and the filling isn’t written.
+    /// Cells all end up aligned to the left, and as the columns get rounded
+    /// up to the tab stops, the lines can be wider than `Display::width()`.
+    pub tab_align: Option<usize>,
+
+    /// The codes to wrap around the contents of the cells in each column,
+    /// from left to right, as a prefix and a suffix — usually ANSI codes
+    /// to give each column its own colour. Columns past the end of the
+    /// list are left as they are, and so are the header and footer rows.
+    ///
+    /// As with `row_styles`, these are assumed to take up no width, so
+    /// they do not affect the layout.
+    pub column_colors: Option<Vec<(String, String)>>,
+}
+
+impl Default for GridOptions {
+    fn default() -> Self {
+        Self {
+            direction:          Direction::LeftToRight,
+            filling:            Filling::Spaces(1),
+            row_styles:         None,
+            packing:            Packing::MaxColumns,
+            row_separator:      None,
+            pad_final_column:   false,
+            indent:             0,
+            header:             None,
+            footer:             None,
+            column_max_widths:  None,
+            wrap_mode:          WrapMode::Truncate,
+            default_alignment:  Alignment::Left,
+            truncation:         Truncation::FromAlignment,
+            tie_break:          TieBreak::MaxColumns,
+            page_break_every:   None,
+            min_gap:            0,
+            text_align:         TextFillAlign::PadBeforeSeparator,
+            row_numbers:        false,
+            tab_align:          None,
+            column_colors:      None,
+        }
+    }
 }
 
+impl GridOptions {
+
+    /// Returns how much space goes in between each pair of columns, which
+    /// is the width of the filling, or the minimum gap if that’s wider.
+    fn separator_width(&self) -> Width {
+        max(self.filling.width(), self.min_gap)
+    }
+}
+
+
+/// The strings to write before and after each row of a grid, alternating
+/// between even and odd rows — usually ANSI codes for zebra striping.
+///
+/// These are assumed to take up no width, so they do not affect the layout.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct RowStyles {
+
+    /// The prefix and suffix for even rows, counting the first row as 0.
+    pub even: (String, String),
+
+    /// The prefix and suffix for odd rows.
+    pub odd: (String, String),
+}
+
+impl RowStyles {
+    fn for_row(&self, row: usize) -> &(String, String) {
+        if row % 2 == 0 { &self.even } else { &self.odd }
+    }
+}
+
+
+/// The policy for picking a layout when fitting a grid into a width.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum Packing {
+
+    /// Use as many columns as will fit, which gives the fewest rows.
+    /// This is the default.
+    MaxColumns,
+
+    /// Use as few columns as possible while keeping to the given number of
+    /// rows, for a taller, narrower grid that still fits on one screen.
+    ///
+    /// If no layout fits into both the width and the number of rows, this
+    /// falls back to the `MaxColumns` layout, which has the fewest rows.
+    MinColumns {
+
+        /// The number of rows the grid should try to stay within — usually
+        /// the height of the terminal.
+        max_rows: usize,
+    },
+}
+
+
+/// What to do with the contents of a cell that’s too wide for its column,
+/// such as when the column has a maximum width.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum WrapMode {
+
+    /// Cut the contents short, marking the cut with an ellipsis.
+    /// This is the default.
+    ///
+    /// The cut is made at the end away from the side the cell is aligned
+    /// to, so right-aligned cells lose their start, and centred cells lose
+    /// a little from each end.
+    Truncate,
+
+    /// Break the contents onto more lines at the spaces between words,
+    /// making the row taller. Words too long to fit on a line by
+    /// themselves get broken up.
+    Word,
+}
+
+
+/// Where to cut short the contents of a cell that’s too wide for its
+/// column, when truncating them.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum Truncation {
+
+    /// Cut the contents at the end away from the side the cell is aligned
+    /// to, marking the cut with `…`, as described for `WrapMode::Truncate`.
+    /// This is the default.
+    FromAlignment,
+
+    /// Always cut the contents at the start, marking the cut with the given
+    /// string, so the end stays visible whatever the alignment — such as
+    /// for the file names at the ends of long paths.
+    LeadingEllipsis(String),
+}
 
-#[derive(PartialEq, Debug)]
-struct Dimensions {
+
+/// How to choose between the layouts that fit when packing a grid with
+/// `Packing::MaxColumns`.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum TieBreak {
+
+    /// Use the layout with the most columns, which gives the fewest rows.
+    /// This is the default.
+    MaxColumns,
+
+    /// Use whichever layout leaves the least empty space at the ends of
+    /// its cells, as measured by `Display::wasted_space`, preferring more
+    /// columns when two layouts waste the same amount.
+    MinWaste,
+}
+
+
+/// Where the padding of a left-aligned cell goes when the columns are
+/// separated by a `Filling::Text`.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum TextFillAlign {
+
+    /// Pad the cell out before the separator, so the separators line up
+    /// down the grid, as in `a  |b`. This is the default.
+    PadBeforeSeparator,
+
+    /// Put the separator straight after the cell’s contents and pad after
+    /// it instead, so the next column’s cells get pushed along, as in
+    /// `a|  b`.
+    PadAfterSeparator,
+}
+
+
+/// The layout that a grid has been fitted into: how many lines it takes up,
+/// and how wide each of its columns is.
+///
+/// This can be kept from a `Display` and compared against later, such as
+/// with `Grid::layout_changed_since`, to tell whether anything moved.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Dimensions {
 
     /// The number of lines in the grid.
     num_lines: Width,
@@ -228,14 +835,28 @@ struct Dimensions {
 }
 
 impl Dimensions {
+
+    /// Returns the number of lines the cells take up, not counting any
+    /// header or footer rows.
+    pub fn num_lines(&self) -> usize {
+        self.num_lines
+    }
+
+    /// Returns the width of each column, from left to right.
+    pub fn widths(&self) -> &[Width] {
+        &self.widths
+    }
+
     fn total_width(&self, separator_width: Width) -> Width {
         if self.widths.is_empty() {
             0
         }
         else {
-            let values = self.widths.iter().sum::<Width>();
-            let separators = separator_width * (self.widths.len() - 1);
-            values + separators
+            // Saturate rather than overflow, so that absurd widths can’t
+            // cause a panic.
+            let values = sum_widths(&self.widths);
+            let separators = separator_width.saturating_mul(self.widths.len() - 1);
+            values.saturating_add(separators)
         }
     }
 }
@@ -243,23 +864,112 @@ impl Dimensions {
 
 /// Everything needed to format the cells with the grid options.
 ///
+/// The cells are usually kept in a vector, so more can be added, but any
+/// other storage for them can be used too; see [`SmallGrid`].
+///
 /// For more information, see the [`term_grid` crate documentation](index.html).
-#[derive(PartialEq, Debug)]
-pub struct Grid {
+///
+/// [`SmallGrid`]: ./type.SmallGrid.html
+#[derive(Debug)]
+pub struct Grid<S = Vec<Cell>> {
     options: GridOptions,
-    cells: Vec<Cell>,
+    cells: S,
     widest_cell_length: Width,
+    narrowest_cell_length: Width,
     width_sum: Width,
     cell_count: usize,
+
+    /// The widths of the strings added with `add_cached` so far.
+    width_cache: BTreeMap<String, Width>,
+}
+
+/// Two grids are equal when they have the same options and the same cells,
+/// however the cells were added: the cache of widths kept by `add_cached`
+/// doesn’t count, and neither do the statistics worked out from the cells.
+impl<S: PartialEq> PartialEq for Grid<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.options == other.options && self.cells == other.cells
+    }
 }
 
+/// A grid that keeps its cells in a fixed-size array, such as
+/// `SmallGrid<[Cell; 4]>`, rather than in a vector on the heap.
+///
+/// This is for small grids that are laid out over and over again, such as
+/// a status display, where allocating room for the cells each time would
+/// be a waste. Create one with `Grid::from_cells`; it gets laid out and
+/// rendered in exactly the same way as a normal grid, though the contents
+/// of the cells, and the rendered text, are still allocated.
+pub type SmallGrid<A> = Grid<A>;
+
 impl Grid {
 
     /// Creates a new grid view with the given options.
     pub fn new(options: GridOptions) -> Self {
         let cells = Vec::new();
-        Self { options, cells, widest_cell_length: 0,
-               width_sum: 0, cell_count: 0 }
+        Self { options, cells, widest_cell_length: 0, narrowest_cell_length: 0,
+               width_sum: 0, cell_count: 0, width_cache: BTreeMap::new() }
+    }
+
+    /// Creates a new grid view with the given options, with room for the
+    /// given number of cells to be added without reallocating.
+    pub fn with_capacity(options: GridOptions, capacity: usize) -> Self {
+        let mut grid = Self::new(options);
+        grid.reserve(capacity);
+        grid
+    }
+
+    /// Returns how many cells the grid can hold without reallocating.
+    /// (See the `Vec::capacity` function.)
+    pub fn capacity(&self) -> usize {
+        self.cells.capacity()
+    }
+
+    /// Creates a new grid out of rows of cells, returning it along with the
+    /// number of columns to pass to `fit_into_columns`.
+    ///
+    /// The cells always get written left to right, whatever direction the
+    /// options give, so that they stay in their rows. Rows that are shorter
+    /// than the longest one are padded out with empty cells.
+    pub fn from_rows<C: Into<Cell>>(options: GridOptions, rows: Vec<Vec<C>>) -> (Self, usize) {
+        let num_columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut grid = Self::with_capacity(GridOptions { direction: Direction::LeftToRight, ..options },
+                                           num_columns.saturating_mul(rows.len()));
+
+        for row in rows {
+            let missing = num_columns - row.len();
+            for cell in row {
+                grid.add(cell.into());
+            }
+
+            for _ in 0 .. missing {
+                grid.add(Cell::from(""));
+            }
+        }
+
+        (grid, num_columns)
+    }
+
+    /// Estimates how many columns a grid would get laid out in by
+    /// `fit_into_width`, from how many cells it’s expected to end up with
+    /// and how wide the widest of them is expected to be, such as for
+    /// sizing a layout before all the cells have arrived.
+    ///
+    /// This assumes every cell is as wide as the widest one, and that the
+    /// columns are packed with `Packing::MaxColumns`, so narrower cells
+    /// usually make room for more columns than this, not fewer. Returns 0
+    /// if there are no cells, or if the widest one can’t fit at all.
+    pub fn estimate_columns(expected_count: usize, expected_max_width: Width, filling: &Filling, maximum_width: Width) -> usize {
+        if expected_count == 0 || expected_max_width > maximum_width {
+            return 0;
+        }
+
+        let filling_width = filling.width();
+        match maximum_width.saturating_add(filling_width).checked_div(expected_max_width.saturating_add(filling_width)) {
+            Some(num_columns)  => num_columns.min(expected_count),
+            None               => expected_count,
+        }
     }
 
     /// Reserves space in the vector for the given number of additional cells
@@ -268,90 +978,822 @@ impl Grid {
         self.cells.reserve(additional)
     }
 
+    /// Shrinks the space reserved for cells down to the number of cells
+    /// there are. (See the `Vec::shrink_to_fit` function.)
+    pub fn shrink_to_fit(&mut self) {
+        self.cells.shrink_to_fit()
+    }
+
     /// Adds another cell onto the vector.
     pub fn add(&mut self, cell: Cell) {
-        if cell.width > self.widest_cell_length {
-            self.widest_cell_length = cell.width;
-        }
-        self.width_sum += cell.width;
-        self.cell_count += 1;
+        self.count_cell(&cell);
         self.cells.push(cell)
     }
 
-    /// Returns a displayable grid that’s been packed to fit into the given
-    /// width in the fewest number of rows.
+    /// Adds a cell containing the given string, measured with its Unicode
+    /// width, and aligned with the default alignment from the options.
+    pub fn add_str(&mut self, string: &str) {
+        let alignment = self.options.default_alignment.clone();
+        self.add(Cell { alignment, ..Cell::from(string) })
+    }
+
+    /// Adds a cell containing the given string, as with `add_str`, but
+    /// looks up its width from the strings that have been added this way
+    /// before, only measuring it the first time it’s seen.
     ///
-    /// Returns `None` if any of the cells has a width greater than the
-    /// maximum width.
-    pub fn fit_into_width(&self, maximum_width: Width) -> Option<Display<'_>> {
-        self.width_dimensions(maximum_width)
-            .map(|dims| Display {
-                grid:       self,
-                dimensions: dims,
-            })
+    /// This saves time when the same strings turn up over and over again,
+    /// such as file extensions in a directory listing.
+    pub fn add_cached(&mut self, string: &str) {
+        let width = match self.width_cache.get(string) {
+            Some(&width)  => width,
+            None          => {
+                let width = UnicodeWidthStr::width(string);
+                self.width_cache.insert(string.into(), width);
+                width
+            },
+        };
+
+        let alignment = self.options.default_alignment.clone();
+        self.add(Cell {
+            contents: string.into(),
+            width,
+            alignment,
+            flexible: false,
+            colspan: 1,
+            leader: None,
+        })
     }
 
-    /// Returns a displayable grid with the given number of columns, and no
-    /// maximum width.
-    pub fn fit_into_columns(&self, num_columns: usize) -> Display<'_> {
-        Display {
-            grid:       self,
-            dimensions: self.columns_dimensions(num_columns),
-        }
+    /// Inserts a cell at the given index, shifting the cells after it along
+    /// by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than the number of cells, just like
+    /// `Vec::insert`.
+    pub fn insert(&mut self, index: usize, cell: Cell) {
+        assert!(index <= self.cells.len(), "insertion index {} is past the end of {} cells", index, self.cells.len());
+        self.count_cell(&cell);
+        self.cells.insert(index, cell)
     }
 
-    fn columns_dimensions(&self, num_columns: usize) -> Dimensions {
-        let mut num_lines = self.cells.len() / num_columns;
-        if self.cells.len() % num_columns != 0 {
-            num_lines += 1;
+    /// Removes and returns the cell at the given index, shifting the cells
+    /// after it back by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds, just like `Vec::remove`.
+    pub fn remove(&mut self, index: usize) -> Cell {
+        let cell = self.cells.remove(index);
+        self.width_sum = self.width_sum.saturating_sub(cell.width);
+        self.cell_count -= 1;
+
+        // The widest or narrowest cell could be the one that’s gone, so
+        // look through the rest to find the new ones.
+        if cell.width == self.widest_cell_length || cell.width == self.narrowest_cell_length {
+            self.widest_cell_length = self.cells.iter().map(|cell| cell.width).max().unwrap_or(0);
+            self.narrowest_cell_length = self.cells.iter().map(|cell| cell.width).min().unwrap_or(0);
         }
 
-        self.column_widths(num_lines, num_columns)
+        cell
     }
 
-    fn column_widths(&self, num_lines: usize, num_columns: usize) -> Dimensions {
-        let mut widths: Vec<Width> = repeat(0).take(num_columns).collect();
-        for (index, cell) in self.cells.iter().enumerate() {
-            let index = match self.options.direction {
-                Direction::LeftToRight  => index % num_columns,
-                Direction::TopToBottom  => index / num_lines,
-            };
-            widths[index] = max(widths[index], cell.width);
+    /// Keeps only the cells that the given function returns `true` for,
+    /// removing the rest. (See the `Vec::retain` function.)
+    pub fn retain<F: FnMut(&Cell) -> bool>(&mut self, f: F) {
+        self.cells.retain(f);
+
+        let (widest, narrowest, sum) = width_stats(&self.cells);
+        self.widest_cell_length = widest;
+        self.narrowest_cell_length = narrowest;
+        self.width_sum = sum;
+        self.cell_count = self.cells.len();
+    }
+
+    /// Updates the running statistics about the cells with a new one.
+    fn count_cell(&mut self, cell: &Cell) {
+        if cell.width > self.widest_cell_length {
+            self.widest_cell_length = cell.width;
+        }
+        if cell.width < self.narrowest_cell_length || self.cell_count == 0 {
+            self.narrowest_cell_length = cell.width;
         }
+        self.width_sum = self.width_sum.saturating_add(cell.width);
+        self.cell_count += 1;
+    }
 
-        Dimensions { num_lines, widths }
+    /// Adds all the cells from another grid onto the end of this one.
+    ///
+    /// This grid keeps its own options, and the other grid’s options are
+    /// thrown away.
+    pub fn merge(&mut self, other: Grid) {
+        self.widest_cell_length = max(self.widest_cell_length, other.widest_cell_length);
+        self.narrowest_cell_length = match (self.cell_count, other.cell_count) {
+            (_, 0)  => self.narrowest_cell_length,
+            (0, _)  => other.narrowest_cell_length,
+            _       => self.narrowest_cell_length.min(other.narrowest_cell_length),
+        };
+        self.width_sum = self.width_sum.saturating_add(other.width_sum);
+        self.cell_count += other.cell_count;
+        self.cells.extend(other.cells);
+        self.width_cache.extend(other.width_cache);
     }
 
-    fn theoretical_max_num_lines(&self, maximum_width: usize) -> usize {
-        // TODO: Make code readable / efficient.
-        let mut theoretical_min_num_cols = 0;
-        let mut col_total_width_so_far = 0;
+    /// Checks that every cell’s width matches the Unicode width of its
+    /// contents, returning the indices of the cells that don’t.
+    ///
+    /// Escape codes, such as colours or hyperlinks, don’t take up any room
+    /// on the screen, so they’re left out when measuring the contents: a
+    /// coloured cell passes as long as its width is that of its text.
+    pub fn validate(&self) -> Result<(), Vec<usize>> {
+        let mismatches: Vec<usize> = self.cells.iter().enumerate()
+            .filter(|&(_, cell)| cell.width != visible_width(&cell.contents))
+            .map(|(index, _)| index)
+            .collect();
+
+        if mismatches.is_empty() { Ok(()) } else { Err(mismatches) }
+    }
 
-        let mut cells = self.cells.clone();
-        cells.sort_unstable_by(|a, b| b.width.cmp(&a.width)); // Sort in reverse order
+    /// Returns whether every cell in the grid has the same width, which is
+    /// also the case when there are fewer than two cells.
+    ///
+    /// When this is true, every column is as wide as every other, so a
+    /// caller can use a simple fixed layout without measuring the columns.
+    pub fn cells_uniform_width(&self) -> bool {
+        self.widest_cell_length == self.narrowest_cell_length
+    }
 
-        for cell in &cells {
-            if cell.width + col_total_width_so_far <= maximum_width {
-                theoretical_min_num_cols += 1;
-                col_total_width_so_far += cell.width;
-            } else {
-                let mut theoretical_max_num_lines = self.cell_count / theoretical_min_num_cols;
-                if self.cell_count % theoretical_min_num_cols != 0 {
-                    theoretical_max_num_lines += 1;
-                }
-                return theoretical_max_num_lines;
-            }
-            col_total_width_so_far += self.options.filling.width()
-        }
+    /// Returns a rough estimate of how many bytes of heap memory the cells
+    /// in this grid are using, counting the space reserved for the cells
+    /// themselves and for each of their contents.
+    ///
+    /// This is only meant as a guide, such as for deciding when to stream
+    /// output rather than build a grid: it doesn’t count everything.
+    pub fn estimated_bytes(&self) -> usize {
+        let cells = self.cells.capacity().saturating_mul(mem::size_of::<Cell>());
+        self.cells.iter().fold(cells, |total, cell| total.saturating_add(cell.contents.capacity()))
+    }
 
-        // If we make it to this point, we have exhausted all cells before
-        // reaching the maximum width; the theoretical max number of lines
-        // needed to display all cells is 1.
-        1
+    /// Sorts the cells that have already been added by their contents.
+    ///
+    /// The cells get laid out in their new order, so this is the same as
+    /// having added them in sorted order in the first place.
+    pub fn sort_cells(&mut self) {
+        self.cells.sort_by(|a, b| a.contents.cmp(&b.contents))
     }
 
-    fn width_dimensions(&self, maximum_width: Width) -> Option<Dimensions> {
-        if self.widest_cell_length > maximum_width {
+    /// Sorts the cells that have already been added with the given
+    /// comparison function. As with `sort_cells`, the layout follows the
+    /// new order.
+    pub fn sort_cells_by<F: FnMut(&Cell, &Cell) -> Ordering>(&mut self, compare: F) {
+        self.cells.sort_by(compare)
+    }
+}
+
+impl<S: AsRef<[Cell]>> Grid<S> {
+
+    /// Creates a new grid view with the given options, holding the given
+    /// cells in whatever they’re already stored in.
+    pub fn from_cells(options: GridOptions, cells: S) -> Self {
+        let (widest_cell_length, narrowest_cell_length, width_sum) = width_stats(cells.as_ref());
+        let cell_count = cells.as_ref().len();
+        Self { options, cells, widest_cell_length, narrowest_cell_length, width_sum, cell_count,
+               width_cache: BTreeMap::new() }
+    }
+
+    /// Returns the options that the grid gets laid out with.
+    pub fn options(&self) -> &GridOptions {
+        &self.options
+    }
+
+    /// Replaces the options that the grid gets laid out with, such as to
+    /// try a different filling, keeping all the cells that have been added.
+    pub fn set_options(&mut self, options: GridOptions) {
+        self.options = options;
+    }
+
+    /// Returns the widest cell along with its index, or the first of them
+    /// if several are equally wide, such as for telling the user which
+    /// entry is too long to fit. Returns `None` if there are no cells.
+    pub fn widest_cell_info(&self) -> Option<(usize, &Cell)> {
+        self.cells().iter().enumerate().find(|&(_, cell)| cell.width == self.widest_cell_length)
+    }
+
+    /// Returns the cells, in the order they were added.
+    fn cells(&self) -> &[Cell] {
+        self.cells.as_ref()
+    }
+
+    /// Returns a displayable grid that’s been packed to fit into the given
+    /// width in the fewest number of rows.
+    ///
+    /// Returns `None` if any of the cells has a width greater than the
+    /// maximum width.
+    pub fn fit_into_width(&self, maximum_width: Width) -> Option<Display<'_, S>> {
+        self.width_dimensions(maximum_width)
+            .map(|dims| self.display(dims))
+    }
+
+    /// Returns a displayable grid that’s been packed to fit into the given
+    /// width, as with `fit_into_width`, but that falls back to putting the
+    /// cells in a single column when nothing fits, rather than giving up.
+    ///
+    /// In that case, lines with cells wider than the maximum width will
+    /// exceed it, but the user still gets to see all of their data.
+    pub fn fit_into_width_best_effort(&self, maximum_width: Width) -> Display<'_, S> {
+        match self.width_dimensions(maximum_width) {
+            Some(dimensions)  => self.display(dimensions),
+            None              => self.fit_into_columns(1),
+        }
+    }
+
+    /// Returns a displayable grid that’s been packed to fit into the given
+    /// width once room has been left for gutters on either side of it, as
+    /// with `fit_into_width`. Every line is indented by the left gutter.
+    ///
+    /// Any indent in the options goes on top of the left gutter, and as with
+    /// the other `fit_into_` methods, isn’t taken out of the width.
+    ///
+    /// Returns `None` if the gutters take up more than the whole width, or
+    /// if any of the cells is too wide for the space left in between them.
+    pub fn fit_into_width_with_gutter(&self, terminal_width: Width, left_gutter: Width, right_gutter: Width) -> Option<Display<'_, S>> {
+        let maximum_width = terminal_width.checked_sub(left_gutter)?.checked_sub(right_gutter)?;
+        self.width_dimensions(maximum_width)
+            .map(|dims| Display { gutter: left_gutter, ..self.display(dims) })
+    }
+
+    /// Returns whether fitting the grid into the given width, as with
+    /// `fit_into_width`, would give a different layout from the given one,
+    /// such as one kept from an earlier display of it. If the layout is the
+    /// same, there’s no need to draw the grid again.
+    ///
+    /// This counts as a change if the grid no longer fits at all.
+    pub fn layout_changed_since(&self, maximum_width: Width, previous: &Dimensions) -> bool {
+        self.width_dimensions(maximum_width).as_ref() != Some(previous)
+    }
+
+    /// Returns a displayable grid that fits into the given width and takes
+    /// up the smallest area — its width times its number of rows — out of
+    /// all the numbers of columns that fit.
+    ///
+    /// This can pick a narrower, taller layout than `fit_into_width` would,
+    /// when fewer columns cut down on the space wasted in them. When two
+    /// layouts have the same area, the one with more columns wins.
+    ///
+    /// Returns `None` if not even a single column fits.
+    pub fn fit_minimal_area(&self, maximum_width: Width) -> Option<Display<'_, S>> {
+        let separator_width = self.options.separator_width();
+        let mut best: Option<(usize, Dimensions)> = None;
+
+        for num_columns in 1 ..= max(self.cell_count, 1) {
+            let dimensions = self.columns_dimensions(num_columns);
+            let total_width = dimensions.total_width(separator_width);
+            if total_width > maximum_width {
+                continue;
+            }
+
+            let area = total_width.saturating_mul(dimensions.num_lines);
+            if best.as_ref().map_or(true, |&(least_area, _)| area <= least_area) {
+                best = Some((area, dimensions));
+            }
+        }
+
+        best.map(|(_, dimensions)| self.display(dimensions))
+    }
+
+    /// Returns a displayable grid that’s been packed to fit into the given
+    /// width, as with `fit_into_width`, and then had its columns widened
+    /// so that it takes up all of the width.
+    ///
+    /// The leftover space is shared out evenly between the columns, with
+    /// any that can’t be shared evenly going to the leftmost ones. Cells
+    /// keep their alignment within the wider columns.
+    pub fn fit_into_width_stretched(&self, maximum_width: Width) -> Option<Display<'_, S>> {
+        let mut dimensions = self.width_dimensions(maximum_width)?;
+
+        let num_columns = dimensions.widths.len();
+        let leftover = maximum_width.saturating_sub(dimensions.total_width(self.options.separator_width()));
+        if let Some(share) = leftover.checked_div(num_columns) {
+            for (i, width) in dimensions.widths.iter_mut().enumerate() {
+                *width += share + if i < leftover % num_columns { 1 } else { 0 };
+            }
+        }
+
+        Some(self.display(dimensions))
+    }
+
+    /// Returns a displayable grid that fits into the given width, using the
+    /// most columns whose layout the given function accepts, such as to
+    /// keep every column narrower than a limit.
+    ///
+    /// Column counts are tried from the most possible downwards, and the
+    /// function is only asked about layouts that fit into the width.
+    /// Returns `None` if it accepts none of them.
+    pub fn fit_into_width_where<F: Fn(&Dimensions) -> bool>(&self, maximum_width: Width, accept: F) -> Option<Display<'_, S>> {
+        let separator_width = self.options.separator_width();
+        (1 ..= max(self.cell_count, 1)).rev()
+            .map(|num_columns| self.columns_dimensions(num_columns))
+            .find(|dimensions| dimensions.total_width(separator_width) <= maximum_width && accept(dimensions))
+            .map(|dimensions| self.display(dimensions))
+    }
+
+    /// Returns a displayable grid with the given number of columns, as with
+    /// `fit_into_columns`, with one of them widened to take up whatever
+    /// space the others leave out of the given width, such as for a column
+    /// of descriptions in a table of details.
+    ///
+    /// Returns `None` if the columns are too wide for the width as they
+    /// are, or if there’s no column with the given index.
+    pub fn fit_with_flex_column(&self, width: Width, num_columns: usize, flex_col: usize) -> Option<Display<'_, S>> {
+        let mut dimensions = self.columns_dimensions(num_columns);
+
+        let total_width = dimensions.total_width(self.options.separator_width());
+        let leftover = width.checked_sub(total_width)?;
+        let flex_width = dimensions.widths.get_mut(flex_col)?;
+        *flex_width = flex_width.saturating_add(leftover);
+
+        Some(self.display(dimensions))
+    }
+
+    /// Returns a displayable grid with the given number of columns, as with
+    /// `fit_into_columns`, with each column’s width kept between the given
+    /// minimum and maximum, such as for a form with fixed-width fields.
+    ///
+    /// Both lists go from left to right, and columns past the end of either
+    /// list have no bound on that side. A maximum of zero counts as one,
+    /// and where the two bounds cross, the minimum wins. Cells too wide for
+    /// their column get cut short or wrapped depending on `wrap_mode`, and
+    /// narrower columns get padded out to their minimum.
+    ///
+    /// Header labels and footer cells aren’t cut short, so a column is never
+    /// made narrower than either of them, whatever its maximum.
+    pub fn fit_into_columns_constrained(&self, num_columns: usize, mins: &[Width], maxes: &[Width]) -> Display<'_, S> {
+        let mut dimensions = self.columns_dimensions(num_columns);
+
+        self.limit_column_widths(&mut dimensions.widths, maxes);
+        for (width, &limit) in dimensions.widths.iter_mut().zip(mins) {
+            *width = max(*width, limit);
+        }
+
+        self.display(dimensions)
+    }
+
+    /// Consumes the grid, returning a displayable grid that’s been packed
+    /// to fit into the given width, as with `fit_into_width`, but that owns
+    /// its cells rather than borrowing them.
+    ///
+    /// Returns `None` if any of the cells has a width greater than the
+    /// maximum width.
+    pub fn into_display(self, maximum_width: Width) -> Option<OwnedDisplay<S>> {
+        self.width_dimensions(maximum_width)
+            .map(|dims| OwnedDisplay {
+                grid:       self,
+                dimensions: dims,
+            })
+    }
+
+    /// Returns a displayable grid that’s been packed to fit into the width
+    /// of the terminal, as with `fit_into_width`.
+    ///
+    /// If the width of the terminal can’t be detected, such as when output
+    /// is being piped to another program, this uses a width of 80 columns.
+    ///
+    /// This is only available with the `terminal` feature.
+    #[cfg(feature = "terminal")]
+    pub fn fit_into_terminal(&self) -> Option<Display<'_, S>> {
+        let detected = terminal_size().map(|(w, _)| w.0 as Width);
+        self.fit_into_width(terminal_width(detected))
+    }
+
+    /// Returns a displayable grid with the given number of columns, and no
+    /// maximum width.
+    ///
+    /// If there aren’t enough cells to put any in some of the columns, those
+    /// columns get left out, so the grid can end up with fewer. Asking for
+    /// more columns than there are cells gives one row with every cell in
+    /// it, and a grid with no cells gets a single empty column.
+    pub fn fit_into_columns(&self, num_columns: usize) -> Display<'_, S> {
+        self.display(self.columns_dimensions(num_columns))
+    }
+
+    /// Returns how wide the grid would be with the given number of columns,
+    /// which is the width that `fit_into_columns` would give it, leaving
+    /// out any indent.
+    ///
+    /// This is useful for telling users how wide their terminal needs to
+    /// be for a particular layout.
+    pub fn min_width_for_columns(&self, num_columns: usize) -> Width {
+        self.columns_dimensions(num_columns).total_width(self.options.separator_width())
+    }
+
+    /// Returns a displayable grid with at most the given number of rows,
+    /// and as many columns as that takes, with no maximum width. The cells
+    /// go in the grid’s direction: with `TopToBottom`, this fills each
+    /// column in turn, like the columns of a newspaper, and always uses
+    /// exactly that many rows.
+    ///
+    /// With the `LeftToRight` direction, the cells still go along the rows,
+    /// filling each one in turn with as many cells as there are columns, so
+    /// the grid can end up with fewer rows than were asked for: six cells
+    /// need two columns to fit into four rows, but then only fill three.
+    ///
+    /// The number of rows is kept to between one and the number of cells.
+    pub fn fit_into_rows(&self, num_lines: usize) -> Display<'_, S> {
+        self.display(self.lines_dimensions(num_lines))
+    }
+
+    /// Returns a displayable grid that fits into a rectangle of the given
+    /// width and number of rows, using as many columns as possible.
+    ///
+    /// Returns `None` if there is no layout that satisfies both limits.
+    pub fn fit_into_rect(&self, maximum_width: Width, maximum_rows: usize) -> Option<Display<'_, S>> {
+        self.rect_dimensions(maximum_width, maximum_rows)
+            .map(|dims| self.display(dims))
+    }
+
+    /// Returns a displayable grid that’s been packed to fit into the given
+    /// number of *bytes* per line, rather than display columns, in the
+    /// fewest number of rows.
+    ///
+    /// This is for sinks that limit the length of lines in bytes, such as
+    /// some network protocols. The padding is still based on each cell’s
+    /// display width, so multi-byte contents take up more bytes than their
+    /// width suggests; the search accounts for this.
+    ///
+    /// Returns `None` if even a single column is too long.
+    pub fn fit_into_byte_width(&self, maximum_bytes: usize) -> Option<Display<'_, S>> {
+        self.byte_width_dimensions(maximum_bytes)
+            .map(|dims| self.display(dims))
+    }
+
+    /// Returns the contents of all the cells joined together on a single
+    /// line, in the order they were added, with the given separator in
+    /// between each pair, such as `", "`.
+    ///
+    /// Nothing is padded or aligned, and there’s no trailing newline, so
+    /// this is handy as a compact fallback for when there are only a few
+    /// cells to show.
+    pub fn to_inline(&self, separator: &str) -> String {
+        let mut output = String::new();
+        for (index, cell) in self.cells().iter().enumerate() {
+            if index > 0 {
+                output.push_str(separator);
+            }
+            output.push_str(&cell.contents);
+        }
+
+        output
+    }
+
+    /// Returns a display of this grid with the given layout.
+    fn display(&self, dimensions: Dimensions) -> Display<'_, S> {
+        let positions = if self.has_spans() { Some(self.positions(dimensions.widths.len())) } else { None };
+        Display { grid: self, dimensions, projection: None, positions, gutter: 0, show_padding: false }
+    }
+
+    fn byte_width_dimensions(&self, maximum_bytes: usize) -> Option<Dimensions> {
+        if self.cell_count == 0 {
+            return Some(Dimensions { num_lines: 0, widths: Vec::new() });
+        }
+
+        for num_lines in 1 ..= self.cell_count {
+            let mut num_columns = self.cell_count / num_lines;
+            if self.cell_count % num_lines != 0 {
+                num_columns += 1;
+            }
+
+            let dimensions = self.column_widths(num_lines, num_columns);
+            if self.line_bytes(&dimensions) <= maximum_bytes {
+                return Some(dimensions);
+            }
+        }
+
+        None
+    }
+
+    /// Returns the most bytes that a line of the given layout could take up,
+    /// counting each cell’s contents plus the spaces used to pad it.
+    fn line_bytes(&self, dimensions: &Dimensions) -> usize {
+        let num_columns = dimensions.widths.len();
+        let mut column_bytes = vec![0; num_columns];
+
+        // Cells cut short to fit into a column with a maximum width can
+        // gain an ellipsis, which may be longer than what it replaced.
+        let ellipsis_bytes = match self.options.truncation {
+            Truncation::LeadingEllipsis(ref ellipsis)  => max(ellipsis.len(), '…'.len_utf8()),
+            Truncation::FromAlignment                  => '…'.len_utf8(),
+        };
+
+        for (&(index, span), cell) in self.placements(dimensions.num_lines, num_columns).iter().zip(self.cells()) {
+            // Spanning cells are padded out over several columns instead.
+            if span > 1 {
+                continue;
+            }
+
+            let column_width = dimensions.widths[index];
+            let content_bytes = if cell.width > column_width { cell.contents.len().saturating_add(ellipsis_bytes) }
+                                else { cell.contents.len() };
+            let padded_bytes = column_width.saturating_sub(cell.width.min(column_width)).saturating_add(content_bytes);
+            column_bytes[index] = max(column_bytes[index], padded_bytes);
+        }
+
+        let gap_spaces = self.options.separator_width() - self.options.filling.width();
+        let separator_bytes = self.options.filling.byte_len().saturating_add(gap_spaces);
+        let separators = separator_bytes.saturating_mul(num_columns.saturating_sub(1));
+        sum_widths(&column_bytes).saturating_add(separators)
+    }
+
+    fn rect_dimensions(&self, maximum_width: Width, maximum_rows: usize) -> Option<Dimensions> {
+        if self.widest_cell_length > maximum_width {
+            return None;
+        }
+
+        if self.cell_count == 0 {
+            return Some(Dimensions { num_lines: 0, widths: Vec::new() });
+        }
+
+        // Fewer lines means more columns, so the first layout that fits the
+        // width is the one we want.
+        let separator_width = self.options.separator_width();
+        for num_lines in 1 ..= maximum_rows.min(self.cell_count) {
+            let mut num_columns = self.cell_count / num_lines;
+            if self.cell_count % num_lines != 0 {
+                num_columns += 1;
+            }
+
+            let dimensions = self.column_widths(num_lines, num_columns);
+            if dimensions.total_width(separator_width) <= maximum_width && dimensions.num_lines <= maximum_rows {
+                return Some(dimensions);
+            }
+        }
+
+        None
+    }
+
+    fn columns_dimensions(&self, num_columns: usize) -> Dimensions {
+        // There has to be at least one column to put the cells in, but
+        // asking for more columns than there are cells would leave empty
+        // columns on the end, and the fillings before them, so leave them
+        // out. Spanning cells can take up more columns than there are cells.
+        let num_columns = if self.has_spans() { max(num_columns, 1) }
+                          else { num_columns.min(self.cells().len()).max(1) };
+
+        let mut num_lines = self.cells().len() / num_columns;
+        if self.cells().len() % num_columns != 0 {
+            num_lines += 1;
+        }
+
+        // Going downwards, the cells can run out before the last columns
+        // too, when there are only enough for fewer full columns.
+        let used_columns = match self.options.direction {
+            Direction::TopToBottom if num_lines > 0  => (self.cells().len() + num_lines - 1) / num_lines,
+            _                                        => num_columns,
+        };
+
+        self.column_widths(num_lines, used_columns)
+    }
+
+    fn lines_dimensions(&self, num_lines: usize) -> Dimensions {
+        if self.cell_count == 0 {
+            return Dimensions { num_lines: 0, widths: Vec::new() };
+        }
+
+        let num_lines = num_lines.min(self.cell_count).max(1);
+
+        let mut num_columns = self.cell_count / num_lines;
+        if self.cell_count % num_lines != 0 {
+            num_columns += 1;
+        }
+
+        // Going along the rows fills up every row but the last, which can
+        // leave rows at the bottom with nothing in them to drop.
+        let num_lines = match self.options.direction {
+            Direction::LeftToRight  => (self.cell_count + num_columns - 1) / num_columns,
+            Direction::TopToBottom  => num_lines,
+        };
+
+        self.column_widths(num_lines, num_columns)
+    }
+
+    /// Cuts the given column widths down to the given maximums, counting a
+    /// maximum of zero as one. Maximums only hold back the cells themselves:
+    /// no column is left narrower than its header label or footer cell.
+    fn limit_column_widths(&self, widths: &mut [Width], limits: &[Width]) {
+        for (width, &limit) in widths.iter_mut().zip(limits) {
+            *width = (*width).min(max(limit, 1));
+        }
+
+        if let Some(ref labels) = self.options.header {
+            for (width, label) in widths.iter_mut().zip(labels) {
+                *width = max(*width, UnicodeWidthStr::width(&label[..]));
+            }
+        }
+
+        if let Some(ref footer) = self.options.footer {
+            for (width, cell) in widths.iter_mut().zip(footer) {
+                *width = max(*width, cell.width);
+            }
+        }
+    }
+
+    fn column_widths(&self, num_lines: usize, num_columns: usize) -> Dimensions {
+        let aligns_on_separators = self.cells().iter().any(|cell| match cell.alignment { Alignment::On(_) => true, _ => false });
+
+        // When every cell is the same width, and each column gets at least
+        // one of them, that width is every column’s width, so there’s no
+        // need to work out where each cell goes.
+        let uniform = self.widest_cell_length == self.narrowest_cell_length && ! aligns_on_separators
+            && ! self.has_spans() && self.fills_every_column(num_lines, num_columns);
+
+        let placements = if uniform { Vec::new() } else { self.placements(num_lines, num_columns) };
+
+        let mut widths: Vec<Width> = vec![if uniform { self.widest_cell_length } else { 0 }; num_columns];
+        for (&(index, span), cell) in placements.iter().zip(self.cells()) {
+            if span == 1 {
+                widths[index] = max(widths[index], cell.width);
+            }
+        }
+
+        // Cells aligned on a separator get shifted along, so their column
+        // needs room for the longest part before it plus the longest after.
+        if aligns_on_separators {
+            let mut prefixes: Vec<Width> = vec![0; num_columns];
+            let mut suffixes: Vec<Width> = vec![0; num_columns];
+            for (&(index, span), cell) in placements.iter().zip(self.cells()) {
+                if let (1, Alignment::On(separator)) = (span, &cell.alignment) {
+                    let prefix = cell.prefix_width(separator);
+                    prefixes[index] = max(prefixes[index], prefix);
+                    suffixes[index] = max(suffixes[index], cell.width - prefix);
+                }
+            }
+
+            for (width, (prefix, suffix)) in widths.iter_mut().zip(prefixes.into_iter().zip(suffixes)) {
+                *width = max(*width, prefix.saturating_add(suffix));
+            }
+        }
+
+        let limits = self.options.column_max_widths.as_ref().map_or(&[][..], |limits| &limits[..]);
+        self.limit_column_widths(&mut widths, limits);
+
+        // A spanning cell that’s wider than the columns it covers, and the
+        // fillings between them, widens each of those columns evenly.
+        let separator_width = self.options.separator_width();
+        for (&(index, span), cell) in placements.iter().zip(self.cells()) {
+            if span == 1 {
+                continue;
+            }
+
+            let columns = &mut widths[index .. index + span];
+            let available = sum_widths(columns).saturating_add(separator_width.saturating_mul(span - 1));
+            if cell.width > available {
+                let shortfall = cell.width - available;
+                for (i, width) in columns.iter_mut().enumerate() {
+                    let extra = shortfall / span + if i < shortfall % span { 1 } else { 0 };
+                    *width = width.saturating_add(extra);
+                }
+            }
+        }
+
+        // Moving spanning cells onto the next row can leave gaps at the ends
+        // of rows, so the cells may need more lines than were asked for.
+        let num_lines = if self.has_spans() {
+            self.positions(num_columns).last().map_or(0, |position| position / num_columns + 1)
+        }
+        else {
+            num_lines
+        };
+
+        Dimensions { num_lines, widths }
+    }
+
+    /// Returns whether any of the cells span more than one column, which
+    /// only happens when they’re written left to right.
+    fn has_spans(&self) -> bool {
+        self.options.direction == Direction::LeftToRight
+            && self.cells().iter().any(|cell| cell.colspan > 1)
+    }
+
+    /// Returns the position that each cell starts at when written left to
+    /// right in the given number of columns, counting along each row in
+    /// turn. A spanning cell takes up several positions, and gets moved to
+    /// the start of the next row if it would run off the end of this one.
+    fn positions(&self, num_columns: usize) -> Vec<usize> {
+        let mut positions = Vec::with_capacity(self.cells().len());
+        let mut position = 0;
+
+        for cell in self.cells() {
+            let span = cell.span(num_columns);
+            if position % num_columns + span > num_columns {
+                position += num_columns - position % num_columns;
+            }
+
+            positions.push(position);
+            position += span;
+        }
+
+        positions
+    }
+
+    /// Returns the column that each cell starts in, and how many columns it
+    /// spans, for a layout with the given number of lines and columns.
+    fn placements(&self, num_lines: usize, num_columns: usize) -> Vec<(usize, usize)> {
+        if self.has_spans() {
+            self.positions(num_columns).into_iter().zip(self.cells())
+                .map(|(position, cell)| (position % num_columns, cell.span(num_columns)))
+                .collect()
+        }
+        else {
+            (0 .. self.cells().len())
+                .map(|index| (self.column_index(index, num_lines, num_columns), 1))
+                .collect()
+        }
+    }
+
+    /// Returns whether a layout with the given number of lines and columns
+    /// puts at least one cell in every column, as long as no cells span.
+    fn fills_every_column(&self, num_lines: usize, num_columns: usize) -> bool {
+        let count = self.cells().len();
+        match self.options.direction {
+            Direction::LeftToRight  => count >= num_columns,
+            Direction::TopToBottom  => num_lines.saturating_mul(num_columns.saturating_sub(1)) < count,
+        }
+    }
+
+    /// Returns the column that the cell at the given index gets placed in,
+    /// for a layout with the given number of lines and columns.
+    fn column_index(&self, index: usize, num_lines: usize, num_columns: usize) -> usize {
+        match self.options.direction {
+            Direction::LeftToRight  => index % num_columns,
+            Direction::TopToBottom  => index / num_lines,
+        }
+    }
+
+    /// Tries to narrow the columns that contain flexible cells until the
+    /// total of the column widths is no more than the given width, returning
+    /// the narrowed layout if there’s one that fits.
+    ///
+    /// A flexible cell can be cut down as far as the width of the widest
+    /// inflexible cell in the grid, so it no longer dominates its column,
+    /// but no further than that (and never below one column, which leaves
+    /// room for an ellipsis).
+    fn shrink_flexible_columns(&self, dimensions: &Dimensions, maximum_width: Width) -> Option<Dimensions> {
+        let flexible_floor = self.cells().iter()
+                                 .filter(|cell| ! cell.flexible)
+                                 .map(|cell| cell.width)
+                                 .fold(1, max);
+
+        let num_columns = dimensions.widths.len();
+        let mut floors = vec![0; num_columns];
+        for (&(index, span), cell) in self.placements(dimensions.num_lines, num_columns).iter().zip(self.cells()) {
+            if span > 1 {
+                continue;
+            }
+
+            let floor = if cell.flexible { cell.width.min(flexible_floor) } else { cell.width };
+            floors[index] = max(floors[index], floor);
+        }
+
+        // Header labels and footer cells can’t be cut down either.
+        let limits = self.options.column_max_widths.as_ref().map_or(&[][..], |limits| &limits[..]);
+        self.limit_column_widths(&mut floors, limits);
+
+        // Give up straight away if even the narrowest columns are too wide.
+        if sum_widths(&floors) > maximum_width {
+            return None;
+        }
+
+        // Otherwise, keep taking a column off the widest column that can
+        // still be narrowed until the whole thing fits.
+        let mut widths = dimensions.widths.clone();
+        let mut total = sum_widths(&widths);
+        while total > maximum_width {
+            let widest = (0 .. num_columns).filter(|&i| widths[i] > floors[i])
+                                           .max_by_key(|&i| widths[i])?;
+            widths[widest] -= 1;
+            total -= 1;
+        }
+
+        Some(Dimensions { num_lines: dimensions.num_lines, widths })
+    }
+
+    fn min_columns_dimensions(&self, maximum_width: Width, maximum_rows: usize) -> Option<Dimensions> {
+        // More lines means fewer columns, so start from the most lines
+        // allowed and work upwards through the column counts.
+        let separator_width = self.options.separator_width();
+        // Going across can take up fewer lines than were asked for, and
+        // the lines it doesn’t need are left out rather than kept empty.
+        for num_lines in (1 ..= maximum_rows.min(self.cell_count)).rev() {
+            let dimensions = self.lines_dimensions(num_lines);
+            if dimensions.total_width(separator_width) <= maximum_width && dimensions.num_lines <= maximum_rows {
+                return Some(dimensions);
+            }
+        }
+
+        None
+    }
+
+    fn width_dimensions(&self, maximum_width: Width) -> Option<Dimensions> {
+        if self.widest_cell_length > maximum_width {
             // Largest cell is wider than maximum width; it is impossible to fit.
             return None;
         }
@@ -361,392 +1803,4745 @@ impl Grid {
         }
 
         if self.cell_count == 1 {
-            let the_cell = &self.cells[0];
+            let the_cell = &self.cells()[0];
             return Some(Dimensions { num_lines: 1, widths: vec![ the_cell.width ] });
         }
 
-        let theoretical_max_num_lines = self.theoretical_max_num_lines(maximum_width);
-        if theoretical_max_num_lines == 1 {
-            // This if—statement is neccesary for the function to work correctly
-            // for small inputs.
-            return Some(Dimensions {
-                num_lines: 1,
-                // I clone self.cells twice. Once here, and once in
-                // self.theoretical_max_num_lines. Perhaps not the best for
-                // performance?
-                widths: self.cells.clone().into_iter().map(|cell| cell.width).collect()
-            });
+        if let Packing::MinColumns { max_rows } = self.options.packing {
+            if let Some(dimensions) = self.min_columns_dimensions(maximum_width, max_rows) {
+                return Some(dimensions);
+            }
         }
-        // Instead of numbers of columns, try to find the fewest number of *lines*
-        // that the output will fit in.
-        let mut smallest_dimensions_yet = None;
-        for num_lines in (1 .. theoretical_max_num_lines).rev() {
 
-            // The number of columns is the number of cells divided by the number
-            // of lines, *rounded up*.
-            let mut num_columns = self.cell_count / num_lines;
-            if self.cell_count % num_lines != 0 {
-                num_columns += 1;
+        // A layout with more columns doesn’t always come out wider, and one
+        // with more lines doesn’t always waste less space, so every layout
+        // that could fit gets tried. Each column is at least as wide as the
+        // narrowest cell in it, or one column for a cell that can shrink,
+        // so there can’t be more columns than fit of the narrowest cells
+        // side by side. Spanning cells and column limits can make columns
+        // narrower still, so there’s no such limit with either of them.
+        let max_num_columns = if self.has_spans() || self.options.column_max_widths.is_some() {
+            self.cell_count
+        }
+        else {
+            let mut widths: Vec<Width> = self.cells().iter()
+                                             .map(|cell| if cell.flexible { cell.width.min(1) } else { cell.width })
+                                             .collect();
+            widths.sort_unstable();
+            fitting_count(&widths, self.options.separator_width(), maximum_width).max(1)
+        };
+
+        // Going across, each number of columns gives a different layout,
+        // but going downwards, each number of lines does. Either way, the
+        // layouts are tried from the fewest lines they could take up to the
+        // most, which is also from the most columns to the fewest.
+        let min_num_lines = (self.cell_count + max_num_columns - 1) / max_num_columns;
+        let num_layouts = match self.options.direction {
+            Direction::LeftToRight  => max_num_columns,
+            Direction::TopToBottom  => self.cell_count + 1 - min_num_lines,
+        };
+
+        let mut best_dimensions_yet: Option<(Width, Dimensions)> = None;
+        for layout in 0 .. num_layouts {
+            let fewest_lines = match self.options.direction {
+                Direction::LeftToRight  => (self.cell_count + max_num_columns - layout - 1) / (max_num_columns - layout),
+                Direction::TopToBottom  => min_num_lines + layout,
+            };
+
+            // Once a layout has fitted, there’s no need to try any that can
+            // only take up more lines, unless it’s the waste that matters.
+            if let (TieBreak::MaxColumns, &Some((_, ref best))) = (self.options.tie_break, &best_dimensions_yet) {
+                if fewest_lines > best.num_lines {
+                    break;
+                }
+            }
+
+            let dimensions = match self.options.direction {
+                Direction::LeftToRight  => self.columns_dimensions(max_num_columns - layout),
+                Direction::TopToBottom  => self.lines_dimensions(fewest_lines),
+            };
+
+            let fitting_dimensions = match self.fitting_dimensions(dimensions, maximum_width) {
+                Some(dimensions)  => dimensions,
+                None              => continue,
+            };
+
+            let waste = match self.options.tie_break {
+                TieBreak::MaxColumns  => 0,
+                TieBreak::MinWaste    => self.display(fitting_dimensions.clone()).wasted_space(),
+            };
+
+            // Among layouts with the same number of lines, the one with
+            // the fewest columns wins, but among layouts wasting the same
+            // space, the one with the most columns, which is found first.
+            let better = match (self.options.tie_break, &best_dimensions_yet) {
+                (_, &None)                                       => true,
+                (TieBreak::MaxColumns, &Some((_, ref best)))     => (fitting_dimensions.num_lines, fitting_dimensions.widths.len())
+                                                                      < (best.num_lines, best.widths.len()),
+                (TieBreak::MinWaste, &Some((least_waste, _)))    => waste < least_waste,
+            };
+
+            if better {
+                best_dimensions_yet = Some((waste, fitting_dimensions));
+            }
+        }
+
+        best_dimensions_yet.map(|(_, dimensions)| dimensions)
+    }
+
+    /// Returns the given dimensions if they fit into the given width along
+    /// with their separators, shrinking any flexible columns if they have
+    /// to be, or `None` if they can’t be made to fit.
+    fn fitting_dimensions(&self, dimensions: Dimensions, maximum_width: Width) -> Option<Dimensions> {
+        // If there are so many columns that the width of the *column
+        // separators* is bigger than the width of the screen, then don’t
+        // even try to tabulate it. The multiplication can overflow, too,
+        // for enormous fillings.
+        let total_separator_width = dimensions.widths.len().saturating_sub(1).checked_mul(self.options.separator_width())?;
+        if maximum_width < total_separator_width {
+            return None;
+        }
+
+        // Remove the separator width from the available space.
+        let adjusted_width = maximum_width - total_separator_width;
+        if sum_widths(&dimensions.widths) <= adjusted_width {
+            Some(dimensions)
+        }
+        else {
+            self.shrink_flexible_columns(&dimensions, adjusted_width)
+        }
+    }
+}
+
+
+/// Consumes the grid, giving back its cells in the order they were added.
+impl IntoIterator for Grid {
+    type Item = Cell;
+    type IntoIter = alloc::vec::IntoIter<Cell>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+/// Borrows the grid’s cells, in the order they were added.
+impl<'grid> IntoIterator for &'grid Grid {
+    type Item = &'grid Cell;
+    type IntoIter = core::slice::Iter<'grid, Cell>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter()
+    }
+}
+
+
+/// A displayable representation of a [`Grid`](struct.Grid.html).
+///
+/// This type implements `Display`, so you can get the textual version
+/// of the grid by calling `.to_string()`.
+#[derive(PartialEq, Debug)]
+pub struct Display<'grid, S = Vec<Cell>> {
+
+    /// The grid to display.
+    grid: &'grid Grid<S>,
+
+    /// The pre-computed column widths for this grid.
+    dimensions: Dimensions,
+
+    /// Which of the laid-out columns are being displayed, if it’s not all
+    /// of them. The widths in `dimensions` are for the displayed columns.
+    projection: Option<Projection>,
+
+    /// Where each cell starts, if any of them span more than one column
+    /// and so the positions can’t be worked out from the indices.
+    positions: Option<Vec<usize>>,
+
+    /// The number of spaces to indent every line by, on top of the indent
+    /// in the grid’s options.
+    gutter: Width,
+
+    /// Whether to draw the padding and fillings as visible characters,
+    /// as `to_string_debug` does.
+    show_padding: bool,
+}
+
+/// A selection of the columns from a grid’s layout.
+#[derive(PartialEq, Debug, Clone)]
+struct Projection {
+
+    /// The laid-out column that each displayed column shows.
+    columns: Vec<usize>,
+
+    /// The number of columns in the original layout, needed to work out
+    /// where the cells go.
+    num_columns: usize,
+}
+
+impl<S: AsRef<[Cell]>> Display<'_, S> {
+
+    /// Returns how many columns this display takes up, based on the separator
+    /// width and the number and width of the columns.
+    pub fn width(&self) -> Width {
+        self.grid_width().saturating_add(self.indent())
+    }
+
+    /// Returns the layout of this display, which can be kept to compare
+    /// against later ones.
+    pub fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    /// Returns the width of the column with the given index, counting from
+    /// the left, or `None` if there aren’t that many columns.
+    pub fn width_of_column(&self, index: usize) -> Option<Width> {
+        self.dimensions.widths.get(index).copied()
+    }
+
+    /// Returns the width of the filling that goes in between each column,
+    /// including any spaces added to make up the minimum gap.
+    pub fn filling_width(&self) -> Width {
+        self.grid.options.separator_width()
+    }
+
+    /// Returns how many rows this display takes up.
+    pub fn row_count(&self) -> usize {
+        self.dimensions.num_lines
+    }
+
+    /// Returns a one-line description of the layout, such as
+    /// `Grid: 12 cells, 4 cols × 3 rows, widths [4,3,6,6], width 21`, for
+    /// pasting into a bug report.
+    pub fn summary(&self) -> String {
+        let widths: Vec<String> = self.dimensions.widths.iter().map(|width| format!("{}", width)).collect();
+        format!("Grid: {} cells, {} cols × {} rows, widths [{}], width {}",
+                self.grid.cells().len(), self.dimensions.widths.len(), self.dimensions.num_lines,
+                widths.join(","), self.width())
+    }
+
+    /// Returns whether this display takes up as many columns as were allotted
+    /// to it.
+    ///
+    /// It’s possible to construct tables that don’t actually use up all the
+    /// columns that they could, such as when there are more columns than
+    /// cells! In this case, a column would have a width of zero. This just
+    /// checks for that.
+    pub fn is_complete(&self) -> bool {
+        self.dimensions.widths.iter().all(|&x| x > 0)
+    }
+
+    /// Returns whether the final row of this display has a cell in every
+    /// column, rather than stopping early.
+    ///
+    /// This is different from `is_complete`, which checks for columns with
+    /// no cells in them at all: a grid can use every column and still have
+    /// a ragged last row.
+    pub fn last_row_is_full(&self) -> bool {
+        let num_columns = self.dimensions.widths.len();
+        if num_columns == 0 || self.dimensions.num_lines == 0 {
+            return true;
+        }
+
+        // A spanning cell counts for all the columns it covers.
+        let last_row = self.dimensions.num_lines - 1;
+        let mut x = 0;
+        while let Some(cell) = self.cell_at(x, last_row) {
+            x += self.span_at(x, cell);
+            if x == num_columns {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns a display of just the given columns of this one’s layout,
+    /// in the given order, without moving any cells between columns.
+    ///
+    /// Indices that are out of range are ignored. The width of the new
+    /// display only counts the columns that were picked.
+    pub fn project_columns(&self, indices: &[usize]) -> Display<'_, S> {
+        let picked: Vec<usize> = indices.iter().cloned()
+                                        .filter(|&x| x < self.dimensions.widths.len())
+                                        .collect();
+
+        let widths = picked.iter().map(|&x| self.dimensions.widths[x]).collect();
+
+        // Projecting a projection picks from the columns that were picked.
+        let projection = match self.projection {
+            Some(ref p)  => Projection { columns: picked.iter().map(|&x| p.columns[x]).collect(), num_columns: p.num_columns },
+            None         => Projection { columns: picked, num_columns: self.dimensions.widths.len() },
+        };
+
+        Display {
+            grid:       self.grid,
+            dimensions: Dimensions { num_lines: self.dimensions.num_lines, widths },
+            projection: Some(projection),
+            positions:  self.positions.clone(),
+            gutter:     self.gutter,
+            show_padding: self.show_padding,
+        }
+    }
+
+    /// Returns the total amount of space in this display taken up by
+    /// padding, rather than by the cells or the fillings between them.
+    ///
+    /// This counts every row as being the full width of the grid (without
+    /// any indent), so the gap at the end of a short last row is included.
+    /// A lower number means a tighter layout.
+    pub fn wasted_space(&self) -> Width {
+        let num_columns = self.dimensions.widths.len();
+        let separators_per_row = self.grid.options.separator_width().saturating_mul(num_columns.saturating_sub(1));
+
+        let mut wasted: Width = 0;
+        for y in 0 .. self.dimensions.num_lines {
+            let used = (0 .. num_columns)
+                .filter_map(|x| self.cell_at(x, y).map(|cell| cell.width.min(self.span_width(x, self.span_at(x, cell)))))
+                .fold(separators_per_row, |sum, width| sum.saturating_add(width));
+
+            wasted = wasted.saturating_add(self.grid_width().saturating_sub(used));
+        }
+
+        wasted
+    }
+
+    /// Returns how wide each line of the rendered rows actually is, from
+    /// top to bottom, including any indent.
+    ///
+    /// Unlike `width()`, this doesn’t count the space after the end of a
+    /// line that stops short, such as the last line of a ragged grid. The
+    /// header and footer rows aren’t included. When the columns are lined
+    /// up with tabs, this is how wide the lines are once the tabs have
+    /// been expanded.
+    pub fn line_widths(&self) -> Vec<Width> {
+        let filling_width = self.grid.options.separator_width();
+        let mut widths = Vec::new();
+
+        for y in 0 .. self.dimensions.num_lines {
+            for line in 0 .. self.row_height(y) {
+                let mut width = self.indent();
+                let mut tabs = self.tab_cursor();
+                self.row_pieces(y, line, |piece| {
+                    match (piece, tabs.as_mut()) {
+                        (Piece::Contents(_, length), Some(tabs))  => tabs.advance(length, length),
+                        (Piece::Padding(_, length), Some(tabs))   => tabs.advance(length, 0),
+                        (Piece::Filling, Some(tabs))              => { tabs.tab_over(filling_width); },
+                        (Piece::Contents(_, length), None)        => width = width.saturating_add(length),
+                        (Piece::Padding(_, length), None)         => width = width.saturating_add(length),
+                        (Piece::Filling, None)                    => width = width.saturating_add(filling_width),
+                    }
+                    Ok(())
+                }).expect("measuring a line cannot fail");
+                widths.push(tabs.map_or(width, |tabs| tabs.tabbed));
+            }
+        }
+
+        widths
+    }
+
+    /// Returns the lines of the rendered grid, without their newlines, such
+    /// as for feeding into a list widget. Joining them back together with a
+    /// newline after each one gives the same text as `to_string()`.
+    ///
+    /// There’s one line for each row, plus any header and footer rows, and
+    /// extra lines for rows with cells wrapped onto more than one line.
+    pub fn as_lines(&self) -> Vec<String> {
+        let mut output = String::new();
+        self.render(&mut output).expect("writing to a String cannot fail");
+        output.split_terminator('\n').map(String::from).collect()
+    }
+
+    /// Returns whether the rendered grid matches the given text, ignoring
+    /// whitespace at the ends of lines and a missing final newline, for
+    /// tests that shouldn’t depend on how the last column gets padded.
+    pub fn matches(&self, expected: &str) -> bool {
+        let mut output = String::new();
+        self.render(&mut output).expect("writing to a String cannot fail");
+        output.lines().map(str::trim_end).eq(expected.lines().map(str::trim_end))
+    }
+
+    /// Renders the grid and rotates the block of text a quarter turn
+    /// clockwise, so the first line ends up as the rightmost column, read
+    /// from top to bottom, such as for vertical labels.
+    ///
+    /// Each character keeps taking up its width, which now runs downwards:
+    /// a double-width character covers two lines, with a space below it.
+    /// Escape sequences are left out, as they can’t be rotated, and spaces
+    /// at the ends of the rotated lines are trimmed.
+    pub fn to_rotated_string(&self) -> String {
+        // Split each line into one string per terminal column, so that
+        // zero-width characters stay with the character before them.
+        let lines: Vec<Vec<String>> = self.as_lines().iter().map(|line| {
+            let mut positions: Vec<String> = Vec::new();
+            for c in strip_ansi(line).chars() {
+                match UnicodeWidthChar::width(c) {
+                    Some(0) | None => if let Some(last) = positions.last_mut() { last.push(c) },
+                    Some(width) => {
+                        let mut position = String::new();
+                        position.push(c);
+                        positions.push(position);
+                        for _ in 1 .. width {
+                            positions.push(" ".into());
+                        }
+                    }
+                }
+            }
+            positions
+        }).collect();
+
+        let height = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let mut output = String::new();
+        for x in 0 .. height {
+            let mut line = String::new();
+            for positions in lines.iter().rev() {
+                line.push_str(positions.get(x).map_or(" ", |position| &position[..]));
+            }
+            output.push_str(line.trim_end_matches(' '));
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Returns an iterator over the columns of this display, from left to
+    /// right, each one containing its cells from top to bottom.
+    ///
+    /// Positions past the end of the cells are skipped rather than included,
+    /// so a column in a ragged grid can be shorter than `row_count()`.
+    pub fn columns(&self) -> impl Iterator<Item = Vec<&Cell>> {
+        (0 .. self.dimensions.widths.len()).map(move |x| {
+            (0 .. self.dimensions.num_lines)
+                .filter_map(|y| self.cell_at(x, y))
+                .collect()
+        })
+    }
+
+    /// Returns the column that the cell with the given index, counting the
+    /// cells in the order they were added, ends up in, such as for
+    /// highlighting the whole column of a selected cell.
+    ///
+    /// Returns `None` if there aren’t that many cells, or if the cell’s
+    /// column has been left out of a projected display.
+    pub fn column_of(&self, cell_index: usize) -> Option<usize> {
+        if cell_index >= self.grid.cells().len() {
+            return None;
+        }
+
+        let num_columns = match self.projection {
+            Some(ref projection)  => projection.num_columns,
+            None                  => self.dimensions.widths.len(),
+        };
+
+        let position = self.positions.as_ref().map_or(cell_index, |positions| positions[cell_index]);
+        let column = match self.grid.options.direction {
+            Direction::LeftToRight  => position % num_columns,
+            Direction::TopToBottom  => position / self.dimensions.num_lines,
+        };
+
+        match self.projection {
+            Some(ref projection)  => projection.columns.iter().position(|&x| x == column),
+            None                  => Some(column),
+        }
+    }
+
+    /// Returns the contents of each column of this display, from left to
+    /// right, as the contents of its cells joined together with newlines,
+    /// from top to bottom.
+    ///
+    /// Positions without a cell become empty lines, so every column has one
+    /// line for each row. The cells aren’t padded.
+    pub fn column_strings(&self) -> Vec<String> {
+        (0 .. self.dimensions.widths.len()).map(|x| {
+            let lines: Vec<&str> = (0 .. self.dimensions.num_lines)
+                .map(|y| self.cell_at(x, y).map_or("", |cell| &cell.contents[..]))
+                .collect();
+            lines.join("\n")
+        }).collect()
+    }
+
+    /// Returns an iterator over every cell in this display along with the
+    /// row and column it ends up in, as `(row, column, cell)`, going along
+    /// each row in turn.
+    ///
+    /// Positions without a cell are skipped.
+    pub fn enumerate_cells(&self) -> impl Iterator<Item = (usize, usize, &Cell)> {
+        let num_columns = self.dimensions.widths.len();
+        (0 .. self.dimensions.num_lines).flat_map(move |y| {
+            (0 .. num_columns).filter_map(move |x| self.cell_at(x, y).map(|cell| (y, x, cell)))
+        })
+    }
+
+    /// Renders the grid onto the end of the given buffer.
+    ///
+    /// This produces the same text as `to_string()`, but lets a buffer be
+    /// reused between renders. The buffer is not cleared first, so call
+    /// `clear` on it yourself to replace its contents.
+    pub fn render_into(&self, buf: &mut String) {
+        self.render(buf).expect("writing to a String cannot fail");
+    }
+
+    /// Renders the grid into any `fmt::Write` target, producing the same
+    /// text as `to_string()` without going through the `Display` trait.
+    pub fn render_to_fmt<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        self.render(w)
+    }
+
+    /// Renders the grid for painting at a fixed place on the screen, with
+    /// each line starting with an ANSI escape sequence that moves the cursor
+    /// to the start of it, rather than being ended by a newline.
+    ///
+    /// The first line goes at the given row and column, counting from one
+    /// as terminals do, and the rest go on the rows below it.
+    pub fn to_positioned_string(&self, origin_row: u16, origin_col: u16) -> String {
+        let mut rendered = String::new();
+        self.render_into(&mut rendered);
+
+        let mut output = String::new();
+        for (offset, line) in rendered.lines().enumerate() {
+            output.push_str(&format!("\x1b[{};{}H", usize::from(origin_row) + offset, origin_col));
+            output.push_str(line);
+        }
+
+        output
+    }
+
+    /// Writes the grid to the given writer one row at a time, calling
+    /// `on_row` with the index of each row after it has been written.
+    ///
+    /// This is meant for showing progress while writing out enormous grids.
+    #[cfg(feature = "std")]
+    pub fn write_to_with_progress<W, F>(&self, w: &mut W, mut on_row: F) -> io::Result<()>
+    where W: io::Write,
+          F: FnMut(usize),
+    {
+        let mut line = String::new();
+        self.write_header(&mut line).expect("writing to a String cannot fail");
+        w.write_all(line.as_bytes())?;
+
+        for y in 0 .. self.dimensions.num_lines {
+            line.clear();
+            for row_line in 0 .. self.row_height(y) {
+                self.write_line(&mut line, y, row_line, None).expect("writing to a String cannot fail");
+                line.push('\n');
+            }
+            self.write_between_rows(&mut line, y).expect("writing to a String cannot fail");
+
+            w.write_all(line.as_bytes())?;
+            on_row(y);
+        }
+
+        line.clear();
+        self.write_footer(&mut line).expect("writing to a String cannot fail");
+        w.write_all(line.as_bytes())
+    }
+
+    /// Renders the grid with its padding made visible, for diagnosing
+    /// alignment problems.
+    ///
+    /// Padding added to align a cell is drawn as `·`, and the filling
+    /// between columns as `¦` (one per column of filling), so the output
+    /// lines up exactly with the normal rendering. Spaces inside a cell’s
+    /// contents are left alone, and everything else, such as the indent,
+    /// row numbers, and row separators, comes out as it normally would.
+    pub fn to_string_debug(&self) -> String {
+        let debug = Display {
+            grid:          self.grid,
+            dimensions:    self.dimensions.clone(),
+            projection:    self.projection.clone(),
+            positions:     self.positions.clone(),
+            gutter:        self.gutter,
+            show_padding:  true,
+        };
+
+        let mut output = String::new();
+        debug.render(&mut output).expect("writing to a String cannot fail");
+        output
+    }
+
+    /// Renders the header and just the first few rows of the grid, followed
+    /// by a line of `...` if any rows had to be left out, such as to show
+    /// a preview of a long listing. The footer still goes at the end.
+    pub fn preview(&self, max_rows: usize) -> String {
+        let mut output = String::new();
+        let shown_rows = max_rows.min(self.dimensions.num_lines);
+
+        self.write_header(&mut output).expect("writing to a String cannot fail");
+
+        for y in 0 .. shown_rows {
+            for line in 0 .. self.row_height(y) {
+                self.write_line(&mut output, y, line, None).expect("writing to a String cannot fail");
+                output.push('\n');
+            }
+
+            if y + 1 < shown_rows {
+                self.write_between_rows(&mut output, y).expect("writing to a String cannot fail");
+            }
+        }
+
+        if shown_rows < self.dimensions.num_lines {
+            output.push_str(&spaces(self.indent()));
+            output.push_str("...\n");
+        }
+
+        self.write_footer(&mut output).expect("writing to a String cannot fail");
+        output
+    }
+
+    /// Renders the grid as plain text, with the ANSI escape sequences in the
+    /// cells and row styles taken out, such as for writing to a log file.
+    ///
+    /// The layout is the same as in the normal rendering: escape sequences
+    /// are already counted as taking up no width, so the columns still line
+    /// up once they’re gone. Both colour codes and hyperlinks are removed.
+    pub fn to_plain_string(&self) -> String {
+        let mut output = String::new();
+        self.render(&mut output).expect("writing to a String cannot fail");
+        strip_ansi(&output)
+    }
+
+    /// Renders the grid, passing the contents of each cell through the given
+    /// function first, such as to colour some of the cells differently.
+    /// The function gets the contents along with the row and column of the
+    /// cell, and returns the text to write instead.
+    ///
+    /// The cells are still padded based on their widths, so the function
+    /// should return text that takes up the same width as it was given:
+    /// anything wider or narrower will throw off the alignment. The header
+    /// and footer rows are written as they are.
+    pub fn map_render<F: Fn(&str, usize, usize) -> String>(&self, f: F) -> String {
+        let mut output = String::new();
+        self.render_mapped(&mut output, Some(&f)).expect("writing to a String cannot fail");
+        output
+    }
+
+    /// Renders the grid as a GitHub-flavoured Markdown table, using the
+    /// header labels as the table’s header if there are any, and the first
+    /// row otherwise.
+    ///
+    /// Each column’s alignment in the table is taken from the alignment of
+    /// its first cell. Pipes in cell contents are escaped, and the cells are
+    /// not padded, as Markdown does its own alignment.
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+        if self.dimensions.num_lines == 0 {
+            return output;
+        }
+
+        // The header labels become the table’s header, and if there aren’t
+        // any then the first row of cells does.
+        let num_columns = self.dimensions.widths.len();
+        let mut rows: Vec<Vec<&str>> = Vec::new();
+        if self.grid.options.header.is_some() {
+            rows.push((0 .. num_columns).map(|x| self.header_label(x)).collect());
+        }
+
+        for y in 0 .. self.dimensions.num_lines {
+            rows.push((0 .. num_columns).map(|x| self.cell_at(x, y).map_or("", |cell| &cell.contents[..])).collect());
+        }
+
+        if let Some(ref footer) = self.grid.options.footer {
+            rows.push((0 .. num_columns).map(|x| {
+                let column = self.layout_column(x);
+                footer.get(column).map_or("", |cell| &cell.contents[..])
+            }).collect());
+        }
+
+        for (index, row) in rows.iter().enumerate() {
+            output.push('|');
+            for x in 0 .. num_columns {
+                output.push(' ');
+                if let Some(contents) = row.get(x) {
+                    output.push_str(&contents.replace('|', "\\|"));
+                }
+                output.push_str(" |");
+            }
+            output.push('\n');
+
+            if index == 0 {
+                output.push('|');
+                for x in 0 .. num_columns {
+                    let alignment = self.cell_at(x, 0).map(|cell| &cell.alignment);
+                    output.push_str(match alignment {
+                        Some(&Alignment::Right) | Some(&Alignment::RightZeroPad)  => " ---: |",
+                        Some(&Alignment::Center { .. })                           => " :---: |",
+                        _                                                         => " :--- |",
+                    });
+                }
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    /// Renders the grid as tab-separated values, for pasting into a
+    /// spreadsheet, with one line per row and a tab in between each column.
+    ///
+    /// The cells go in the same places as in the normal rendering, but
+    /// without any padding, and positions without a cell become empty
+    /// fields. The header labels and footer cells get rows of their own.
+    /// Tabs and newlines in cell contents are turned into spaces so they
+    /// don’t split up the fields.
+    pub fn to_tsv(&self) -> String {
+        let mut output = String::new();
+        if self.dimensions.num_lines == 0 {
+            return output;
+        }
+
+        let num_columns = self.dimensions.widths.len();
+        let mut push_row = |fields: Vec<&str>| {
+            for (x, field) in fields.into_iter().enumerate() {
+                if x > 0 {
+                    output.push('\t');
+                }
+                output.extend(field.chars().map(|c| if c == '\t' || c == '\n' { ' ' } else { c }));
+            }
+            output.push('\n');
+        };
+
+        if self.grid.options.header.is_some() {
+            push_row((0 .. num_columns).map(|x| self.header_label(x)).collect());
+        }
+
+        for y in 0 .. self.dimensions.num_lines {
+            push_row((0 .. num_columns).map(|x| self.cell_at(x, y).map_or("", |cell| &cell.contents[..])).collect());
+        }
+
+        if let Some(ref footer) = self.grid.options.footer {
+            push_row((0 .. num_columns).map(|x| footer.get(self.layout_column(x)).map_or("", |cell| &cell.contents[..])).collect());
+        }
+
+        output
+    }
+
+    /// Renders the grid as a table with box-drawing borders around it and
+    /// between all its columns and rows, with a space either side of each
+    /// cell. The filling isn’t used, as the borders take its place.
+    ///
+    /// Cells that span columns have no border between those columns, and
+    /// the junctions in the rules above and below them are drawn to match.
+    pub fn to_bordered_string(&self) -> String {
+        let mut output = String::new();
+        let num_columns = self.dimensions.widths.len();
+        if num_columns == 0 || self.dimensions.num_lines == 0 {
+            return output;
+        }
+
+        let indent = spaces(self.indent());
+        let every_column = vec![true; num_columns - 1];
+        let has_header = self.grid.options.header.is_some();
+
+        let first_row = self.bordered_boundaries(0);
+        let top = if has_header { &every_column } else { &first_row };
+        output.push_str(&indent);
+        output.push_str(&self.bordered_rule(('┌', '┐'), None, Some(top)));
+        output.push('\n');
+
+        if has_header {
+            output.push_str(&indent);
+            output.push('│');
+            for x in 0 .. num_columns {
+                let label = self.header_label(x);
+                output.push(' ');
+                output.push_str(label);
+                output.push_str(&spaces(self.dimensions.widths[x].saturating_sub(UnicodeWidthStr::width(label))));
+                output.push_str(" │");
+            }
+            output.push('\n');
+
+            output.push_str(&indent);
+            output.push_str(&self.bordered_rule(('├', '┤'), Some(&every_column), Some(&first_row)));
+            output.push('\n');
+        }
+
+        for y in 0 .. self.dimensions.num_lines {
+            for line in 0 .. self.row_height(y) {
+                output.push_str(&indent);
+                self.bordered_line(&mut output, |x| self.cell_at(x, y).map(|cell| (cell, self.span_at(x, cell))), line);
+                output.push('\n');
+            }
+
+            let above = self.bordered_boundaries(y);
+            if y + 1 < self.dimensions.num_lines {
+                let below = self.bordered_boundaries(y + 1);
+                output.push_str(&indent);
+                output.push_str(&self.bordered_rule(('├', '┤'), Some(&above), Some(&below)));
+                output.push('\n');
+            }
+            else if let Some(ref footer) = self.grid.options.footer {
+                output.push_str(&indent);
+                output.push_str(&self.bordered_rule(('├', '┤'), Some(&above), Some(&every_column)));
+                output.push('\n');
+
+                output.push_str(&indent);
+                self.bordered_line(&mut output, |x| footer.get(self.layout_column(x)).map(|cell| (cell, 1)), 0);
+                output.push('\n');
+            }
+        }
+
+        let bottom = if self.grid.options.footer.is_some() { every_column } else { self.bordered_boundaries(self.dimensions.num_lines - 1) };
+        output.push_str(&indent);
+        output.push_str(&self.bordered_rule(('└', '┘'), Some(&bottom), None));
+        output.push('\n');
+
+        output
+    }
+}
+
+impl<S: AsRef<[Cell]>> Display<'_, S> {
+
+    /// Returns the number of spaces that every line gets indented by.
+    fn indent(&self) -> Width {
+        self.grid.options.indent.saturating_add(self.gutter).saturating_add(self.row_number_width())
+    }
+
+    /// Returns how much room the row numbers take up, including the space
+    /// after them, which is nothing if the rows aren’t being numbered.
+    fn row_number_width(&self) -> Width {
+        if ! self.grid.options.row_numbers {
+            return 0;
+        }
+
+        let mut digits = 1;
+        let mut rest = self.dimensions.num_lines / 10;
+        while rest > 0 {
+            digits += 1;
+            rest /= 10;
+        }
+
+        digits + 1
+    }
+
+    /// Returns the width of the columns and the fillings between them,
+    /// without any indentation.
+    fn grid_width(&self) -> Width {
+        self.dimensions.total_width(self.grid.options.separator_width())
+    }
+
+    /// Returns the index of the cell at the given column and row, following
+    /// the grid’s direction, or `None` if the cells run out before then.
+    fn cell_index(&self, x: usize, y: usize) -> Option<usize> {
+        let (x, num_columns) = match self.projection {
+            Some(ref projection)  => (projection.columns[x], projection.num_columns),
+            None                  => (x, self.dimensions.widths.len()),
+        };
+
+        let num = match self.grid.options.direction {
+            Direction::LeftToRight  => y * num_columns + x,
+            Direction::TopToBottom  => y + self.dimensions.num_lines * x,
+        };
+
+        match self.positions {
+            Some(ref positions)  => positions.binary_search(&num).ok(),
+            None                 => if num < self.grid.cells().len() { Some(num) } else { None },
+        }
+    }
+
+    /// Returns how many of the displayed columns the given cell, starting
+    /// in the given column, spans. Spanning cells only take up their first
+    /// column when the columns have been projected.
+    fn span_at(&self, x: usize, cell: &Cell) -> usize {
+        if self.positions.is_none() || self.projection.is_some() {
+            1
+        }
+        else {
+            cell.span(self.dimensions.widths.len() - x)
+        }
+    }
+
+    /// Returns the width of the given number of columns, starting at the
+    /// given one, including the fillings between them.
+    fn span_width(&self, x: usize, span: usize) -> Width {
+        let separators = self.grid.options.separator_width().saturating_mul(span - 1);
+        sum_widths(&self.dimensions.widths[x .. x + span]).saturating_add(separators)
+    }
+
+    /// Returns which column of the grid’s layout the given displayed
+    /// column shows, which differs when the columns have been projected.
+    fn layout_column(&self, x: usize) -> usize {
+        self.projection.as_ref().map_or(x, |projection| projection.columns[x])
+    }
+
+    /// Returns the cell at the given column and row, if there is one.
+    fn cell_at(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.cell_index(x, y).map(|num| &self.grid.cells()[num])
+    }
+
+    /// Returns the columns that the cells in the given row start in, from
+    /// left to right, skipping the ones covered by spanning cells.
+    fn starting_columns(&self, y: usize) -> Vec<usize> {
+        let mut columns = Vec::new();
+        let mut x = 0;
+        while x < self.dimensions.widths.len() {
+            x += match self.cell_at(x, y) {
+                Some(cell)  => { columns.push(x); self.span_at(x, cell) },
+                None        => 1,
+            };
+        }
+
+        columns
+    }
+
+    /// Returns the widest part before the given separator of any of the
+    /// cells in the given column that are aligned on it.
+    fn column_prefix_width(&self, x: usize, separator: &str) -> Width {
+        (0 .. self.dimensions.num_lines)
+            .filter_map(|y| self.cell_at(x, y))
+            .filter(|cell| cell.alignment == Alignment::On(separator.into()))
+            .map(|cell| cell.prefix_width(separator))
+            .fold(0, max)
+    }
+
+    /// Splits a single row of the grid into the pieces that make it up,
+    /// passing each one to the given function in order.
+    ///
+    /// Every renderer goes through this, so they all agree on where the
+    /// padding and the fillings go.
+    ///
+    /// Rows containing wrapped cells take up more than one line, so this
+    /// also takes which of the row’s lines to split up.
+    fn row_pieces<F>(&self, y: usize, line: usize, piece: F) -> fmt::Result
+    where F: FnMut(Piece<'_>) -> fmt::Result
+    {
+        self.line_pieces(|x| self.cell_at(x, y).map(|cell| (cell, self.span_at(x, cell))), line, piece)
+    }
+
+    /// Returns how many lines the given row takes up, which is more than
+    /// one if any of its cells get wrapped.
+    fn row_height(&self, y: usize) -> usize {
+        if self.grid.options.wrap_mode != WrapMode::Word {
+            return 1;
+        }
+
+        let mut height = 1;
+        let mut x = 0;
+        while x < self.dimensions.widths.len() {
+            let span = match self.cell_at(x, y) {
+                Some(cell) => {
+                    let span = self.span_at(x, cell);
+                    let column_width = self.span_width(x, span);
+                    if cell.width > column_width {
+                        height = max(height, wrap_words(&cell.contents, column_width).len());
+                    }
+                    span
+                },
+                None => 1,
+            };
+
+            x += span;
+        }
+
+        height
+    }
+
+    /// Returns the part of the given cell’s contents that goes on the given
+    /// line of its row, along with its width, cutting it short or wrapping
+    /// it if it’s too wide for its column.
+    fn cell_line<'a>(&self, cell: &'a Cell, column_width: Width, line: usize) -> (Cow<'a, str>, Width) {
+        if cell.width <= column_width {
+            return if line == 0 { (Cow::Borrowed(&*cell.contents), cell.width) }
+                   else         { (Cow::Borrowed(""), 0) };
+        }
+
+        match self.grid.options.wrap_mode {
+            WrapMode::Truncate if line == 0 => {
+                let (truncated, width) = match self.grid.options.truncation {
+                    Truncation::LeadingEllipsis(ref ellipsis) => truncate_start(&cell.contents, column_width, ellipsis),
+
+                    // Otherwise, cells get cut short at the end furthest from
+                    // the side they’re aligned to, so the end that lines up
+                    // stays visible.
+                    Truncation::FromAlignment => match cell.alignment {
+                        Alignment::Right | Alignment::RightZeroPad  => truncate_start(&cell.contents, column_width, "…"),
+                        Alignment::Center { .. }                    => truncate_both(&cell.contents, column_width),
+                        _                                           => truncate(&cell.contents, column_width),
+                    },
+                };
+                (Cow::Owned(truncated), width)
+            },
+            WrapMode::Truncate => (Cow::Borrowed(""), 0),
+            WrapMode::Word => {
+                match wrap_words(&cell.contents, column_width).into_iter().nth(line) {
+                    Some(text) => {
+                        let width = chars_width(&text);
+                        (Cow::Owned(text), width)
+                    },
+                    None => (Cow::Borrowed(""), 0),
+                }
+            },
+        }
+    }
+
+    /// Splits the footer row into pieces, as `row_pieces` does for the
+    /// rows of cells. Footer cells never span columns.
+    fn footer_pieces<F>(&self, piece: F) -> fmt::Result
+    where F: FnMut(Piece<'_>) -> fmt::Result
+    {
+        let footer = match self.grid.options.footer {
+            Some(ref footer)  => footer,
+            None              => return Ok(()),
+        };
+
+        self.line_pieces(|x| {
+            let column = self.layout_column(x);
+            footer.get(column).map(|cell| (cell, 1))
+        }, 0, piece)
+    }
+
+    /// Splits a line of cells into pieces, given the cell that starts in
+    /// each column and how many columns it spans, and which of the lines
+    /// of those cells to use.
+    fn line_pieces<'a, C, F>(&'a self, cell_for: C, line: usize, mut piece: F) -> fmt::Result
+    where C: Fn(usize) -> Option<(&'a Cell, usize)>,
+          F: FnMut(Piece<'_>) -> fmt::Result
+    {
+        let last_column = self.dimensions.widths.len().saturating_sub(1);
+        let pad_final_column = self.grid.options.pad_final_column;
+
+        // The line ends after the last cell in the row, which is before the
+        // final column if the cells run out early.
+        let last_cell_column = (0 .. self.dimensions.widths.len()).rev()
+                                   .find(|&x| cell_for(x).is_some());
+
+        let mut x = 0;
+        while x < self.dimensions.widths.len() && Some(x) <= last_cell_column {
+            // Abandon a line mid-way through if that’s where the cells end,
+            // but leave a gap for a missing cell that has others after it,
+            // which can happen when the columns have been projected.
+            let (cell, span) = match cell_for(x) {
+                Some(cell_and_span) => cell_and_span,
+                None if Some(x) < last_cell_column => {
+                    piece(Piece::Padding(' ', self.dimensions.widths[x]))?;
+                    piece(Piece::Filling)?;
+                    x += 1;
+                    continue;
+                },
+                None => {
+                    x += 1;
+                    continue;
+                },
+            };
+
+            // A spanning cell is rendered once, across all its columns.
+            let column_width = self.span_width(x, span);
+            let end = x + span - 1;
+
+            // The last cell on a line doesn’t need any trailing spaces after
+            // it, or a filling, even a visible one, as there’s nothing left
+            // for it to separate, unless every line is being padded out.
+            let is_last_cell = Some(x) == last_cell_column && ! pad_final_column;
+            let trim_end = is_last_cell && cell.leader.is_none();
+
+            let has_filling = end != last_column && ! is_last_cell;
+            let is_left = match cell.alignment { Alignment::Left => true, _ => false };
+
+            if has_filling && is_left && self.pads_after_filling() {
+                // Hold back the padding until the filling’s been written.
+                let mut padding = None;
+                self.cell_pieces(x, cell, column_width, line, trim_end, &mut |p| match p {
+                    Piece::Padding(c, length)  => { padding = Some((c, length)); Ok(()) },
+                    p                          => piece(p),
+                })?;
+
+                piece(Piece::Filling)?;
+                if let Some((c, length)) = padding {
+                    piece(Piece::Padding(c, length))?;
+                }
+            }
+            else {
+                self.cell_pieces(x, cell, column_width, line, trim_end, &mut piece)?;
+
+                if has_filling {
+                    piece(Piece::Filling)?;
+                }
+            }
+
+            x += span;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether left-aligned cells get padded after the filling that
+    /// follows them, rather than before it.
+    fn pads_after_filling(&self) -> bool {
+        self.grid.options.text_align == TextFillAlign::PadAfterSeparator
+            && match self.grid.options.filling { Filling::Text(_) => true, _ => false }
+    }
+
+    /// Splits a single cell into the pieces that make it up, padding it out
+    /// to the given column width unless it’s at the end of a line.
+    fn cell_pieces<'a, F>(&self, x: usize, cell: &'a Cell, column_width: Width, line: usize, trim_end: bool, piece: &mut F) -> fmt::Result
+    where F: FnMut(Piece<'a>) -> fmt::Result
+    {
+        // Cells aligned on a separator get shifted right until theirs
+        // lines up with the rest of the column’s.
+        let lead = match cell.alignment {
+            Alignment::On(ref separator) => self.column_prefix_width(x, separator).saturating_sub(cell.prefix_width(separator)),
+            _                            => 0,
+        };
+
+        // Flexible cells, and cells in columns with a maximum width, can
+        // end up in columns narrower than they are.
+        let (contents, width) = self.cell_line(cell, column_width, line);
+
+        let extra_spaces = column_width.saturating_sub(width);
+        let pad_char = cell.leader.unwrap_or(' ');
+
+        match cell.alignment {
+            Alignment::Left => {
+                piece(Piece::Contents(contents, width))?;
+
+                if ! trim_end {
+                    piece(Piece::Padding(pad_char, extra_spaces))?;
+                }
+            },
+            Alignment::Right => {
+                piece(Piece::Padding(pad_char, extra_spaces))?;
+                piece(Piece::Contents(contents, width))?;
+            },
+            Alignment::RightZeroPad => {
+                piece(Piece::Padding('0', extra_spaces))?;
+                piece(Piece::Contents(contents, width))?;
+            },
+            Alignment::Center { extra_on } => {
+                let lead = extra_on.centred_lead(extra_spaces);
+
+                piece(Piece::Padding(' ', lead))?;
+                piece(Piece::Contents(contents, width))?;
+
+                if ! trim_end {
+                    piece(Piece::Padding(pad_char, extra_spaces - lead))?;
+                }
+            },
+            Alignment::On(_) => {
+                let lead = lead.min(extra_spaces);
+                piece(Piece::Padding(' ', lead))?;
+                piece(Piece::Contents(contents, width))?;
+
+                if ! trim_end {
+                    piece(Piece::Padding(pad_char, extra_spaces - lead))?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Writes the whole grid, one line per row.
+    fn render<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        self.render_mapped(f, None)
+    }
+
+    /// Writes the whole grid, one line per row, passing the contents of
+    /// each cell through the given function first if there is one.
+    fn render_mapped<W: fmt::Write>(&self, f: &mut W, map: ContentsMap<'_>) -> fmt::Result {
+        self.write_header(f)?;
+
+        for y in 0 .. self.dimensions.num_lines {
+            for line in 0 .. self.row_height(y) {
+                self.write_line(f, y, line, map)?;
+                writeln!(f)?;
+            }
+
+            self.write_between_rows(f, y)?;
+        }
+
+        self.write_footer(f)
+    }
+
+    /// Writes one of the lines of output for the given row, including any
+    /// row styles, but without the trailing newline.
+    fn write_line<W: fmt::Write>(&self, f: &mut W, y: usize, line: usize, map: ContentsMap<'_>) -> fmt::Result {
+        let style = self.grid.options.row_styles.as_ref().map(|styles| styles.for_row(y));
+
+        // Only the first line of a wrapped row gets its number.
+        let number_width = self.row_number_width();
+        f.write_str(&spaces(self.indent() - number_width))?;
+        if number_width > 0 && line == 0 {
+            write!(f, "{:>1$} ", y + 1, number_width - 1)?;
+        }
+        else {
+            f.write_str(&spaces(number_width))?;
+        }
+
+        if let Some((prefix, _)) = style {
+            f.write_str(prefix)?;
+        }
+
+        self.write_row(f, y, line, map)?;
+
+        if let Some((_, suffix)) = style {
+            f.write_str(suffix)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the row of column labels, if there is one, including its
+    /// newline.
+    fn write_header<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        if self.grid.options.header.is_none() || self.dimensions.num_lines == 0 {
+            return Ok(());
+        }
+
+        f.write_str(&spaces(self.indent()))?;
+        let mut tabs = self.tab_cursor();
+        self.header_pieces(|piece| self.write_piece(f, piece, &mut tabs))?;
+        writeln!(f)
+    }
+
+    /// Writes the footer row, if there is one, including its newline.
+    fn write_footer<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        if self.grid.options.footer.is_none() || self.dimensions.num_lines == 0 {
+            return Ok(());
+        }
+
+        f.write_str(&spaces(self.indent()))?;
+        let mut tabs = self.tab_cursor();
+        self.footer_pieces(|piece| self.write_piece(f, piece, &mut tabs))?;
+        writeln!(f)
+    }
+
+    /// Returns the label for the given column, which is blank if there
+    /// isn’t one.
+    fn header_label(&self, x: usize) -> &str {
+        let column = self.layout_column(x);
+        let label = self.grid.options.header.as_ref().and_then(|labels| labels.get(column));
+        label.map_or("", |label| &label[..])
+    }
+
+    /// Splits the row of column labels into pieces, as `row_pieces` does
+    /// for the rows of cells. Labels are always aligned to the left.
+    fn header_pieces<F>(&self, mut piece: F) -> fmt::Result
+    where F: FnMut(Piece<'_>) -> fmt::Result
+    {
+        let num_columns = self.dimensions.widths.len();
+
+        // As with cells, the line stops after the last label.
+        let last_label_column = match (0 .. num_columns).rev().find(|&x| ! self.header_label(x).is_empty()) {
+            Some(x)  => x,
+            None     => return Ok(()),
+        };
+        let pad_final_column = self.grid.options.pad_final_column;
+
+        for x in 0 .. num_columns {
+            let label = self.header_label(x);
+            let trim_end = x == last_label_column && ! pad_final_column;
+
+            piece(Piece::Contents(Cow::Borrowed(label), UnicodeWidthStr::width(label)))?;
+            if trim_end {
+                break;
+            }
+
+            let extra_spaces = self.dimensions.widths[x].saturating_sub(UnicodeWidthStr::width(label));
+            if x + 1 != num_columns && self.pads_after_filling() {
+                piece(Piece::Filling)?;
+                piece(Piece::Padding(' ', extra_spaces))?;
+                continue;
+            }
+
+            piece(Piece::Padding(' ', extra_spaces))?;
+
+            if x + 1 != num_columns {
+                piece(Piece::Filling)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns, for each gap between two columns, whether the given row has
+    /// a border in it, which it doesn’t if a cell spans across the gap.
+    fn bordered_boundaries(&self, y: usize) -> Vec<bool> {
+        let num_columns = self.dimensions.widths.len();
+        let mut boundaries = vec![true; num_columns - 1];
+
+        let mut x = 0;
+        while x < num_columns {
+            let span = self.cell_at(x, y).map_or(1, |cell| self.span_at(x, cell));
+            for gap in &mut boundaries[x .. x + span - 1] {
+                *gap = false;
+            }
+
+            x += span;
+        }
+
+        boundaries
+    }
+
+    /// Draws a horizontal rule across the whole table, with the given
+    /// characters at its ends, and a junction at each gap between columns
+    /// that joins up with the borders in the rows above and below it.
+    fn bordered_rule(&self, (left, right): (char, char), above: Option<&[bool]>, below: Option<&[bool]>) -> String {
+        let mut rule = String::new();
+        rule.push(left);
+
+        for (x, &width) in self.dimensions.widths.iter().enumerate() {
+            rule.push_str(&padding('─', width.saturating_add(2)));
+
+            if x + 1 == self.dimensions.widths.len() {
+                break;
+            }
+
+            let up   = above.map_or(false, |gaps| gaps[x]);
+            let down = below.map_or(false, |gaps| gaps[x]);
+            rule.push(match (up, down) {
+                (true,  true)   => '┼',
+                (true,  false)  => '┴',
+                (false, true)   => '┬',
+                (false, false)  => '─',
+            });
+        }
+
+        rule.push(right);
+        rule
+    }
+
+    /// Writes one line of a bordered table, given the cell that starts in
+    /// each column and how many columns it spans, without its newline.
+    /// Every cell gets padded out, and missing ones are left blank.
+    fn bordered_line<'a, C>(&'a self, output: &mut String, cell_for: C, line: usize)
+    where C: Fn(usize) -> Option<(&'a Cell, usize)>
+    {
+        output.push('│');
+
+        let mut x = 0;
+        while x < self.dimensions.widths.len() {
+            let (cell, span) = match cell_for(x) {
+                Some(cell_and_span) => cell_and_span,
+                None => {
+                    output.push(' ');
+                    output.push_str(&spaces(self.dimensions.widths[x]));
+                    output.push_str(" │");
+                    x += 1;
+                    continue;
+                },
+            };
+
+            // A spanning cell also takes up the borders between its columns
+            // and the spaces either side of them.
+            let width = sum_widths(&self.dimensions.widths[x .. x + span]).saturating_add(3 * (span - 1));
+
+            output.push(' ');
+            self.cell_pieces(x, cell, width, line, false, &mut |piece| {
+                match piece {
+                    Piece::Contents(contents, _)  => output.push_str(&contents),
+                    Piece::Padding(c, length)     => output.push_str(&padding(c, length)),
+                    Piece::Filling                => {},
+                }
+                Ok(())
+            }).expect("writing to a String cannot fail");
+            output.push_str(" │");
+
+            x += span;
+        }
+    }
+
+    /// Writes anything that should go in between the given row and the next
+    /// one, such as group separators, including their newlines.
+    fn write_between_rows<W: fmt::Write>(&self, f: &mut W, y: usize) -> fmt::Result {
+        let is_last_row = y + 1 == self.dimensions.num_lines;
+
+        if let Some((every, ref text)) = self.grid.options.row_separator {
+            if every > 0 && (y + 1) % every == 0 && ! is_last_row {
+                let indent = spaces(self.indent());
+                writeln!(f, "{}{}", indent, repeat_to_width(text, self.grid_width()))?;
+            }
+        }
+
+        if let Some(every) = self.grid.options.page_break_every {
+            if every > 0 && (y + 1) % every == 0 && ! is_last_row {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a single row of the grid, without the trailing newline,
+    /// passing the contents of each cell through the given function first
+    /// if there is one.
+    fn write_row<W: fmt::Write>(&self, f: &mut W, y: usize, line: usize, map: ContentsMap<'_>) -> fmt::Result {
+        // Each cell’s contents come out in the order of the columns they
+        // start in, which are needed for the function and the colours.
+        let colors = self.grid.options.column_colors.as_ref();
+        let mut columns = match (map, colors) {
+            (None, None)  => Vec::new(),
+            _             => self.starting_columns(y),
+        }.into_iter();
+
+        let mut tabs = self.tab_cursor();
+        self.row_pieces(y, line, |piece| match piece {
+            Piece::Contents(contents, width) if map.is_some() || colors.is_some() => {
+                let x = columns.next().unwrap_or(0);
+                let contents = match map {
+                    Some(map)  => Cow::Owned(map(&contents, y, x)),
+                    None       => contents,
+                };
+
+                let color = colors.and_then(|colors| colors.get(self.layout_column(x)))
+                                  .filter(|_| ! contents.is_empty());
+                if let Some((prefix, _)) = color {
+                    f.write_str(prefix)?;
+                }
+
+                self.write_piece(f, Piece::Contents(contents, width), &mut tabs)?;
+
+                if let Some((_, suffix)) = color {
+                    f.write_str(suffix)?;
+                }
+
+                Ok(())
+            },
+            piece => self.write_piece(f, piece, &mut tabs),
+        })
+    }
+
+    /// Writes a single piece of a line, lining the columns up with tabs
+    /// rather than padding if there’s a cursor to keep track of them.
+    fn write_piece<W: fmt::Write>(&self, f: &mut W, piece: Piece<'_>, tabs: &mut Option<TabCursor>) -> fmt::Result {
+        match (piece, tabs.as_mut()) {
+            (Piece::Contents(contents, width), tabs) => {
+                if let Some(tabs) = tabs {
+                    tabs.advance(width, width);
+                }
+                f.write_str(&contents)
+            },
+            (Piece::Padding(_, length), Some(tabs))                 => { tabs.advance(length, 0); Ok(()) },
+            (Piece::Padding(_, length), None) if self.show_padding  => f.write_str(&padding('·', length)),
+            (Piece::Padding(c, length), None)                       => f.write_str(&padding(c, length)),
+            (Piece::Filling, Some(tabs))                            => tabs.write_tabs(f, self.grid.options.separator_width()),
+            (Piece::Filling, None) if self.show_padding             => f.write_str(&padding('¦', self.grid.options.separator_width())),
+            (Piece::Filling, None)                                  => self.write_filling(f),
+        }
+    }
+
+    /// Returns a cursor for lining up the columns of a line with tabs, if
+    /// the grid’s options ask for that, for a line starting after the
+    /// indent.
+    fn tab_cursor(&self) -> Option<TabCursor> {
+        let tab_width = max(self.grid.options.tab_align?, 1);
+        let separator_width = self.grid.options.separator_width();
+
+        // Each column starts at the first tab stop past the end of the
+        // previous one, and where it would have started with padding.
+        let indent = self.indent();
+        let mut starts = vec![ (indent, indent) ];
+        for &width in &self.dimensions.widths {
+            let (spaced, tabbed) = starts[starts.len() - 1];
+            let next_stop = (tabbed.saturating_add(width) / tab_width).saturating_add(1).saturating_mul(tab_width);
+            starts.push((spaced.saturating_add(width).saturating_add(separator_width), next_stop));
+        }
+
+        Some(TabCursor { tab_width, starts, spaced: indent, tabbed: indent })
+    }
+
+    /// Writes the filling that goes in between two columns, with spaces
+    /// around it to make up the minimum gap if it’s too narrow.
+    fn write_filling<W: fmt::Write>(&self, f: &mut W) -> fmt::Result {
+        let options = &self.grid.options;
+        let extra = options.separator_width() - options.filling.width();
+        f.write_str(&spaces(extra / 2))?;
+
+        match options.filling {
+            Filling::Spaces(n)    => f.write_str(&spaces(n))?,
+            Filling::Text(ref t)  => f.write_str(t)?,
+        }
+
+        f.write_str(&spaces(extra - extra / 2))
+    }
+}
+
+/// Keeps track of how far along a line has got when its columns are lined
+/// up with tabs, both as it would be with padding and as it is with tabs,
+/// so that the tabs after each cell reach the start of the next column.
+struct TabCursor {
+
+    /// The distance between tab stops.
+    tab_width: Width,
+
+    /// Where each column starts with padding, and where it starts with
+    /// tabs, from left to right.
+    starts: Vec<(Width, Width)>,
+
+    /// How far along the line would have got with padding.
+    spaced: Width,
+
+    /// How far along the line has actually got.
+    tabbed: Width,
+}
+
+impl TabCursor {
+
+    /// Moves the cursor along past the given widths, as the line would be
+    /// with padding and as it actually is.
+    fn advance(&mut self, spaced: Width, tabbed: Width) {
+        self.spaced = self.spaced.saturating_add(spaced);
+        self.tabbed = self.tabbed.saturating_add(tabbed);
+    }
+
+    /// Writes the tabs that take the line to the start of the next column,
+    /// in place of a filling of the given width. There’s always at least
+    /// one, even if a cell overran its column.
+    fn write_tabs<W: fmt::Write>(&mut self, f: &mut W, filling_width: Width) -> fmt::Result {
+        let tabs = self.tab_over(filling_width);
+        f.write_str(&"\t".repeat(tabs))
+    }
+
+    /// Moves the cursor along to the start of the next column, as
+    /// `write_tabs` does, returning how many tabs that takes.
+    fn tab_over(&mut self, filling_width: Width) -> usize {
+        self.spaced = self.spaced.saturating_add(filling_width);
+        let spaced = self.spaced;
+        let target = self.starts.iter().find(|&&(start, _)| start >= spaced).map_or(0, |&(_, start)| start);
+
+        let tabs = max((target / self.tab_width).saturating_sub(self.tabbed / self.tab_width), 1);
+        self.tabbed = (self.tabbed / self.tab_width + tabs).saturating_mul(self.tab_width);
+        tabs
+    }
+}
+
+/// One part of a rendered row, as produced by `Display::row_pieces`.
+enum Piece<'grid> {
+
+    /// The contents of a cell, which may have been truncated to fit, and
+    /// how wide they are.
+    Contents(Cow<'grid, str>, Width),
+
+    /// Padding used to align a cell within its column: the character to
+    /// pad with, and how many of them.
+    Padding(char, Width),
+
+    /// The filling in between two columns.
+    Filling,
+}
+
+/// A function to pass each cell’s contents through as it gets rendered,
+/// along with its row and column, if there is one.
+type ContentsMap<'f> = Option<&'f dyn Fn(&str, usize, usize) -> String>;
+
+impl<S: AsRef<[Cell]>> fmt::Display for Display<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.render(f)
+    }
+}
+
+
+/// A displayable representation of a [`Grid`](struct.Grid.html) that owns
+/// the grid, so it can be kept around without borrowing anything.
+///
+/// This type implements `Display` too, and the borrowing version of it is
+/// available through `display`.
+#[derive(PartialEq, Debug)]
+pub struct OwnedDisplay<S = Vec<Cell>> {
+
+    /// The grid to display.
+    grid: Grid<S>,
+
+    /// The pre-computed column widths for this grid.
+    dimensions: Dimensions,
+}
+
+impl<S: AsRef<[Cell]>> OwnedDisplay<S> {
+
+    /// Returns a display that borrows from this one, for using any of its
+    /// methods.
+    pub fn display(&self) -> Display<'_, S> {
+        self.grid.display(self.dimensions.clone())
+    }
+
+    /// Gives back the grid, so more cells can be added to it.
+    pub fn into_grid(self) -> Grid<S> {
+        self.grid
+    }
+}
+
+impl<S: AsRef<[Cell]>> fmt::Display for OwnedDisplay<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display().render(f)
+    }
+}
+
+
+/// The width to use for a terminal whose width can’t be detected.
+#[cfg(feature = "terminal")]
+const DEFAULT_TERMINAL_WIDTH: Width = 80;
+
+/// Picks the width to fit a grid into, given the detected terminal width.
+#[cfg(feature = "terminal")]
+fn terminal_width(detected: Option<Width>) -> Width {
+    detected.unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Returns the rest of the string after the given prefix, if it starts with
+/// it. (`str::strip_prefix` is too new for the supported Rust versions.)
+fn strip_prefix<'a>(string: &'a str, prefix: &str) -> Option<&'a str> {
+    if string.starts_with(prefix) { Some(&string[prefix.len() ..]) } else { None }
+}
+
+/// Returns the Unicode width of the given text once any ANSI escape
+/// sequences have been taken out of it.
+fn visible_width(text: &str) -> Width {
+    if text.contains('\x1b') {
+        UnicodeWidthStr::width(&*strip_ansi(text))
+    }
+    else {
+        UnicodeWidthStr::width(text)
+    }
+}
+
+/// Removes the ANSI escape sequences from the given text: control sequences
+/// such as colour codes, which end with a letter or other final byte, and
+/// operating system commands such as hyperlinks, which end with a bell or a
+/// string terminator.
+fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40' ..= '\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    else if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    output
+}
+
+/// Returns the widest and narrowest of the given cells’ widths, and the sum
+/// of all of them, which are all zero if there are no cells.
+fn width_stats(cells: &[Cell]) -> (Width, Width, Width) {
+    let mut widths = cells.iter().map(|cell| cell.width);
+    let first = widths.next().unwrap_or(0);
+    widths.fold((first, first, first), |(widest, narrowest, sum), width| {
+        (max(widest, width), narrowest.min(width), sum.saturating_add(width))
+    })
+}
+
+/// Returns how many of the given widths, starting from the first, fit side
+/// by side into the given width with a separator in between each pair.
+fn fitting_count(widths: &[Width], separator_width: Width, maximum_width: Width) -> usize {
+    let mut total: Width = 0;
+    for (count, &width) in widths.iter().enumerate() {
+        // A total too big to count can’t fit into any width.
+        let gap = if count == 0 { 0 } else { separator_width };
+        match total.checked_add(gap).and_then(|total| total.checked_add(width)) {
+            Some(with_width) if with_width <= maximum_width => total = with_width,
+            _ => return count,
+        }
+    }
+
+    widths.len()
+}
+
+/// Adds up a list of widths, saturating instead of overflowing.
+fn sum_widths(widths: &[Width]) -> Width {
+    widths.iter().fold(0, |sum, &width| sum.saturating_add(width))
+}
+
+/// Repeats the given text as many whole times as fit into the given width.
+fn repeat_to_width(text: &str, width: Width) -> String {
+    let text_width = UnicodeWidthStr::width(text);
+    if text_width == 0 {
+        return String::new();
+    }
+
+    text.repeat(width / text_width)
+}
+
+/// Cuts the given string down to fit into the given width, ending it with
+/// an ellipsis to show that something’s missing. Returns the new string
+/// along with its width.
+fn truncate(string: &str, width: Width) -> (String, Width) {
+    if width == 0 {
+        return (String::new(), 0);
+    }
+
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+    for c in string.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if truncated_width + char_width > width - 1 {
+            break;
+        }
+
+        truncated.push(c);
+        truncated_width += char_width;
+    }
+
+    truncated.push('…');
+    (truncated, truncated_width + 1)
+}
+
+/// Cuts the given string down to fit into the given width from its start,
+/// beginning it with the given ellipsis instead. Returns the new string
+/// along with its width.
+///
+/// An ellipsis too wide for the width gets swapped for a single `…`.
+fn truncate_start(string: &str, width: Width, ellipsis: &str) -> (String, Width) {
+    if width == 0 {
+        return (String::new(), 0);
+    }
+
+    let ellipsis_width = UnicodeWidthStr::width(ellipsis);
+    if ellipsis_width > width {
+        return truncate_start(string, width, "…");
+    }
+
+    let mut kept = Vec::new();
+    let mut truncated_width = 0;
+    for c in string.chars().rev() {
+        let char_width = c.width().unwrap_or(0);
+        if truncated_width + char_width > width - ellipsis_width {
+            break;
+        }
+
+        kept.push(c);
+        truncated_width += char_width;
+    }
+
+    let truncated = ellipsis.chars().chain(kept.into_iter().rev()).collect();
+    (truncated, truncated_width + ellipsis_width)
+}
+
+/// Cuts the given string down to fit into the given width from both ends,
+/// keeping its middle between two ellipses. Returns the new string along
+/// with its width.
+fn truncate_both(string: &str, width: Width) -> (String, Width) {
+    if width < 2 {
+        return truncate(string, width);
+    }
+
+    // Cut half of what won’t fit off the start, rounding down, and let the
+    // end lose the rest.
+    let excess = UnicodeWidthStr::width(string).saturating_sub(width - 2);
+    let mut cut_width = 0;
+    let middle = string.trim_start_matches(|c: char| {
+        let fits = cut_width >= excess / 2;
+        if ! fits {
+            cut_width += c.width().unwrap_or(0);
+        }
+        ! fits
+    });
+
+    let (truncated, truncated_width) = truncate(middle, width - 1);
+    (format!("…{}", truncated), truncated_width + 1)
+}
+
+/// Breaks the given text into lines no wider than the given width, at the
+/// spaces between words where it can, and in the middle of any word too
+/// long to fit on a line by itself.
+fn wrap_words(text: &str, width: Width) -> Vec<String> {
+    let width = max(width, 1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for word in text.split(' ').filter(|word| ! word.is_empty()) {
+        let word_width = chars_width(word);
+
+        if ! line.is_empty() && line_width + 1 + word_width <= width {
+            line.push(' ');
+            line.push_str(word);
+            line_width += 1 + word_width;
+            continue;
+        }
+
+        if ! line.is_empty() {
+            lines.push(line);
+            line = String::new();
+            line_width = 0;
+        }
+
+        for c in word.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if line_width + char_width > width && ! line.is_empty() {
+                lines.push(line);
+                line = String::new();
+                line_width = 0;
+            }
+
+            // A character too wide for a line of its own, such as a
+            // double-width one in a single column, gets cut short.
+            if char_width > width {
+                line.push('…');
+                line_width += 1;
+            }
+            else {
+                line.push(c);
+                line_width += char_width;
+            }
+        }
+    }
+
+    if ! line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Returns the width of the given text, adding up the widths of its
+/// characters the same way `wrap_words` and `truncate` do, so that control
+/// characters take up no room.
+fn chars_width(text: &str) -> Width {
+    text.chars().map(|c| c.width().unwrap_or(0)).fold(0, Width::saturating_add)
+}
+
+/// Pad a string with the given number of spaces.
+fn spaces(length: usize) -> String {
+    padding(' ', length)
+}
+
+/// Repeat the given padding character the given number of times.
+fn padding(character: char, length: usize) -> String {
+    (0 .. length).map(|_| character).collect()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn no_items() {
+        let grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        let display = grid.fit_into_width(40).unwrap();
+
+        assert_eq!(display.dimensions.num_lines, 0);
+        assert!(display.dimensions.widths.is_empty());
+
+        assert_eq!(display.width(), 0);
+    }
+
+    #[test]
+    fn one_item() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("1"));
+
+        let display = grid.fit_into_width(40).unwrap();
+
+        assert_eq!(display.dimensions.num_lines, 1);
+        assert_eq!(display.dimensions.widths, vec![ 1 ]);
+
+        assert_eq!(display.width(), 1);
+    }
+
+    #[test]
+    fn one_item_exact_width() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("1234567890"));
+
+        let display = grid.fit_into_width(10).unwrap();
+
+        assert_eq!(display.dimensions.num_lines, 1);
+        assert_eq!(display.dimensions.widths, vec![ 10 ]);
+
+        assert_eq!(display.width(), 10);
+    }
+
+    #[test]
+    fn one_item_just_over() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("1234567890!"));
+
+        assert_eq!(grid.fit_into_width(10), None);
+    }
+
+    #[test]
+    fn two_small_items() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("1"));
+        grid.add(Cell::from("2"));
+
+        let display = grid.fit_into_width(40).unwrap();
+
+        assert_eq!(display.dimensions.num_lines, 1);
+        assert_eq!(display.dimensions.widths, vec![ 1, 1 ]);
+
+        assert_eq!(display.width(), 1 + 2 + 1);
+    }
+
+    #[test]
+    fn two_medium_size_items() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("hello there"));
+        grid.add(Cell::from("how are you today?"));
+
+        let display = grid.fit_into_width(40).unwrap();
+
+        assert_eq!(display.dimensions.num_lines, 1);
+        assert_eq!(display.dimensions.widths, vec![ 11, 18 ]);
+
+        assert_eq!(display.width(), 11 + 2 + 18);
+    }
+
+    #[test]
+    fn two_big_items() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("nuihuneihsoenhisenouiuteinhdauisdonhuisudoiosadiuohnteihaosdinhteuieudi"));
+        grid.add(Cell::from("oudisnuthasuouneohbueobaugceoduhbsauglcobeuhnaeouosbubaoecgueoubeohubeo"));
+
+        assert_eq!(grid.fit_into_width(40), None);
+    }
+
+    #[test]
+    fn that_example_from_earlier() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let bits = "one  two three  four\nfive six seven  eight\nnine ten eleven twelve\n";
+        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
+        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+    }
+
+    #[test]
+    fn number_grid_with_pipe() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let bits = "one |two|three |four\nfive|six|seven |eight\nnine|ten|eleven|twelve\n";
+        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
+        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+    }
+
+    #[test]
+    fn numbers_right() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::Right;
+            grid.add(cell);
+        }
+
+        let bits = " one two  three   four\nfive six  seven  eight\nnine ten eleven twelve\n";
+        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
+        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+    }
+
+    #[test]
+    fn numbers_right_pipe() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::Right;
+            grid.add(cell);
+        }
+
+        let bits = " one|two| three|  four\nfive|six| seven| eight\nnine|ten|eleven|twelve\n";
+        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
+        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+    }
+
+    #[test]
+    fn huge_separator() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(100),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        grid.add("a".into());
+        grid.add("b".into());
+
+        // The separator can’t fit, but a single column doesn’t need one.
+        assert_eq!(grid.fit_into_width(99).unwrap().to_string(), "a\nb\n");
+    }
+
+    #[test]
+    fn huge_yet_unused_separator() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(100),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        grid.add("abcd".into());
+
+        let display = grid.fit_into_width(99).unwrap();
+
+        assert_eq!(display.dimensions.num_lines, 1);
+        assert_eq!(display.dimensions.widths, vec![ 4 ]);
+
+        assert_eq!(display.width(), 4);
+    }
+
+    #[test]
+    fn last_row_ragged() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["1", "2", "3", "4", "5", "6", "7"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.row_count(), 3);
+        assert!(!display.last_row_is_full());
+    }
+
+    #[test]
+    fn last_row_full() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["1", "2", "3", "4", "5", "6", "7", "8", "9"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.row_count(), 3);
+        assert!(display.last_row_is_full());
+    }
+
+    #[test]
+    fn rect_limited_by_width() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_rect(24, 10).unwrap();
+        assert_eq!(display.row_count(), 3);
+        assert_eq!(display.dimensions.widths.len(), 4);
+        assert!(display.width() <= 24);
+    }
+
+    #[test]
+    fn rect_limited_by_rows() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        // Twelve rows would be enough for a single column, but two rows
+        // need six columns, which is more than the width allows.
+        assert!(grid.fit_into_rect(24, 12).is_some());
+        assert_eq!(grid.fit_into_rect(24, 2), None);
+
+        let display = grid.fit_into_rect(40, 2).unwrap();
+        assert_eq!(display.row_count(), 2);
+        assert_eq!(display.dimensions.widths.len(), 6);
+    }
+
+    #[test]
+    fn rect_empty() {
+        let grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        let display = grid.fit_into_rect(10, 0).unwrap();
+        assert_eq!(display.row_count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn options_hash() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+        set.insert(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn zebra_stripes() {
+        let mut grid = Grid::new(GridOptions {
+            filling:     Filling::Spaces(1),
+            direction:   Direction::LeftToRight,
+            row_styles:  Some(RowStyles {
+                even: ("<e>".into(), "</e>".into()),
+                odd:  ("<o>".into(), "</o>".into()),
+            }),
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_width(24).unwrap();
+        let output = display.to_string();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "<e>one  two three  four</e>");
+        assert_eq!(lines[1], "<o>five six seven  eight</o>");
+        assert_eq!(lines[2], "<e>nine ten eleven twelve</e>");
+        assert_eq!(display.width(), 22);
+    }
+
+    #[test]
+    fn columns_of_example() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_width(24).unwrap();
+        let columns: Vec<Vec<&str>> = display.columns()
+            .map(|column| column.iter().map(|cell| &*cell.contents).collect())
+            .collect();
+
+        assert_eq!(columns, vec![
+            vec![ "one",   "five",  "nine"   ],
+            vec![ "two",   "six",   "ten"    ],
+            vec![ "three", "seven", "eleven" ],
+            vec![ "four",  "eight", "twelve" ],
+        ]);
+    }
+
+    #[test]
+    fn columns_skip_empty_positions() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["1", "2", "3", "4", "5"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        let lengths: Vec<usize> = display.columns().map(|column| column.len()).collect();
+        assert_eq!(lengths, vec![ 3, 2 ]);
+    }
+
+    #[test]
+    fn right_zero_pad() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["12345", "42", "abc", "de"] {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::RightZeroPad;
+            grid.add(cell);
+        }
+
+        let bits = "12345 abc\n00042 0de\n";
+        assert_eq!(grid.fit_into_columns(2).to_string(), bits);
+    }
+
+    #[test]
+    fn sorting_cells() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["b", "d", "a", "c"] {
+            grid.add(Cell::from(*s));
+        }
+
+        grid.sort_cells();
+        assert_eq!(grid.fit_into_columns(2).to_string(), "a c\nb d\n");
+
+        grid.sort_cells_by(|a, b| b.contents.cmp(&a.contents));
+        assert_eq!(grid.fit_into_columns(2).to_string(), "d b\nc a\n");
+    }
+
+    #[test]
+    fn zero_columns() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        grid.add("a".into());
+        grid.add("b".into());
+
+        assert_eq!(grid.fit_into_columns(0).to_string(), "a\nb\n");
+    }
+
+    #[test]
+    fn enormous_cell_widths() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for _ in 0 .. 3 {
+            grid.add(Cell { contents: "x".into(), width: Width::max_value() - 1, alignment: Alignment::Left, flexible: false, colspan: 1, leader: None });
+        }
+
+        assert!(grid.fit_into_width(Width::max_value()).is_some());
+        assert_eq!(grid.fit_into_columns(3).width(), Width::max_value());
+    }
+
+    #[test]
+    fn enormous_filling() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(Width::max_value() / 2),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c", "d", "e"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert!(grid.fit_into_width(Width::max_value()).is_some());
+        assert_eq!(grid.fit_into_width(10).unwrap().row_count(), 5);
+    }
+
+    #[test]
+    fn zero_width_cells_in_zero_width() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        grid.add("".into());
+        grid.add("".into());
+
+        let _ = grid.fit_into_width(0);
+    }
+
+    #[test]
+    fn hyperlinked_cell() {
+        let link = Cell::from_hyperlinked("docs", "https://docs.rs/term_grid/");
+        assert_eq!(link.contents, "\x1b]8;;https://docs.rs/term_grid/\x1b\\docs\x1b]8;;\x1b\\");
+        assert_eq!(link.width, 4);
+
+        let mut plain_grid = Grid::new(GridOptions::default());
+        let mut link_grid = Grid::new(GridOptions::default());
+
+        for s in &["readme", "a", "b", "licence"] {
+            plain_grid.add(Cell::from(*s));
+            link_grid.add(Cell::from(*s));
+        }
+
+        plain_grid.add(Cell::from("docs"));
+        link_grid.add(link.clone());
+
+        let plain = plain_grid.fit_into_columns(2).to_string();
+        let linked = link_grid.fit_into_columns(2).to_string();
+        assert_eq!(linked, plain.replace("docs", &link.contents));
+    }
+
+    #[test]
+    fn debug_padding() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let bits = "one·¦two¦three·¦four\nfive¦six¦seven·¦eight\nnine¦ten¦eleven¦twelve\n";
+        assert_eq!(grid.fit_into_width(24).unwrap().to_string_debug(), bits);
+    }
+
+    #[test]
+    fn debug_padding_keeps_content_spaces() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        let mut cell = Cell::from("a b");
+        cell.alignment = Alignment::Right;
+        grid.add(cell);
+        grid.add(Cell::from("c"));
+        grid.add(Cell::from("de fg"));
+
+        assert_eq!(grid.fit_into_columns(2).to_string_debug(), "··a b¦c\nde fg\n");
+    }
+
+    #[test]
+    fn packing_policies() {
+        let mut max_grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            packing:    Packing::MaxColumns,
+            ..GridOptions::default()
+        });
+
+        let mut min_grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            packing:    Packing::MinColumns { max_rows: 4 },
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            max_grid.add(Cell::from(*s));
+            min_grid.add(Cell::from(*s));
+        }
+
+        let display = max_grid.fit_into_width(80).unwrap();
+        assert_eq!(display.row_count(), 1);
+        assert_eq!(display.dimensions.widths.len(), 12);
+
+        let display = min_grid.fit_into_width(80).unwrap();
+        assert_eq!(display.row_count(), 4);
+        assert_eq!(display.dimensions.widths.len(), 3);
+    }
+
+    #[test]
+    fn min_columns_falls_back() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            packing:    Packing::MinColumns { max_rows: 1 },
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        // One row would be too wide, so it uses the fewest rows it can.
+        let display = grid.fit_into_width(24).unwrap();
+        assert_eq!(display.row_count(), 3);
+    }
+
+    #[test]
+    fn min_columns_across_leaves_no_empty_rows() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            packing:    Packing::MinColumns { max_rows: 5 },
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        // Five rows need three columns, and three columns going across
+        // fill up only four rows.
+        let display = grid.fit_into_width(80).unwrap();
+        assert_eq!(display.row_count(), 4);
+        assert_eq!(display.dimensions.widths.len(), 3);
+        assert_eq!(display.to_string(), "one   two    three\nfour  five   six\nseven eight  nine\nten   eleven twelve\n");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_with_progress() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_width(24).unwrap();
+        let mut output = Vec::new();
+        let mut rows = Vec::new();
+        display.write_to_with_progress(&mut output, |row| rows.push(row)).unwrap();
+
+        assert_eq!(rows, vec![ 0, 1, 2 ]);
+        assert_eq!(String::from_utf8(output).unwrap(), display.to_string());
+    }
+
+    #[test]
+    fn filling_text_accepted() {
+        assert_eq!(Filling::text("|"), Ok(Filling::Text("|".into())));
+        assert_eq!(Filling::text(" │ "), Ok(Filling::Text(" │ ".into())));
+    }
+
+    #[test]
+    fn filling_text_rejected() {
+        assert_eq!(Filling::text("a\nb"), Err(FillingError::ControlCharacter('\n')));
+        assert_eq!(Filling::text("\t"), Err(FillingError::ControlCharacter('\t')));
+    }
+
+    #[test]
+    fn row_separators() {
+        let mut grid = Grid::new(GridOptions {
+            filling:        Filling::Spaces(1),
+            direction:      Direction::LeftToRight,
+            row_separator:  Some((3, "-".into())),
+            ..GridOptions::default()
+        });
+
+        for i in 0 .. 14 {
+            grid.add(Cell::from(format!("{:02}", i)));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.width(), 5);
+
+        let bits = "00 01\n02 03\n04 05\n-----\n06 07\n08 09\n10 11\n-----\n12 13\n";
+        assert_eq!(display.to_string(), bits);
+    }
+
+    #[test]
+    fn no_trailing_whitespace() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(2),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let mut cell = Cell::from("4");
+        cell.alignment = Alignment::Right;
+        grid.add(cell);
+        grid.add(Cell::from("five"));
+
+        assert_eq!(grid.fit_into_columns(2).to_string(), "one    two\nthree    4\nfive\n");
+    }
+
+    #[test]
+    fn right_aligned_final_column() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "bb", "ccc", "d", "eeee"] {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::Right;
+            grid.add(cell);
+        }
+
+        assert_eq!(grid.fit_into_columns(2).to_string(), "  a    d\n bb eeee\nccc\n");
+    }
+
+    #[test]
+    fn pad_final_column() {
+        let mut grid = Grid::new(GridOptions {
+            filling:           Filling::Spaces(1),
+            direction:         Direction::TopToBottom,
+            pad_final_column:  true,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "bb", "ccc", "d", "eeee"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_columns(2).to_string(), "a   d   \nbb  eeee\nccc \n");
+    }
+
+    #[test]
+    fn byte_width() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for _ in 0 .. 4 {
+            grid.add(Cell::from("ééé"));
+        }
+
+        // Each cell is three columns wide, but six bytes long, so two
+        // columns would fit into eight display columns but not eight bytes.
+        assert_eq!(grid.fit_into_columns(2).width(), 7);
+
+        let display = grid.fit_into_byte_width(8).unwrap();
+        assert_eq!(display.dimensions.widths.len(), 1);
+        assert!(display.to_string().lines().all(|line| line.len() <= 8));
+
+        assert_eq!(grid.fit_into_byte_width(5), None);
+    }
+
+    #[test]
+    fn byte_width_text_filling() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("│".into()),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c", "d"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // The box-drawing separator is one column wide but three bytes long.
+        let display = grid.fit_into_byte_width(7).unwrap();
+        assert_eq!(display.dimensions.widths.len(), 2);
+        assert!(display.to_string().lines().all(|line| line.len() <= 7));
+    }
+
+    #[test]
+    fn render_into_buffer() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_width(24).unwrap();
+        let mut buf = String::new();
+
+        display.render_into(&mut buf);
+        assert_eq!(buf, display.to_string());
+
+        display.render_into(&mut buf);
+        assert_eq!(buf, display.to_string().repeat(2));
+
+        buf.clear();
+        display.render_into(&mut buf);
+        assert_eq!(buf, display.to_string());
+    }
+
+    #[test]
+    fn top_to_bottom_short_last_column() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for i in 0 .. 10 {
+            grid.add(Cell::from(format!("{}", i)));
+        }
+
+        let display = grid.fit_into_columns(3);
+        let lengths: Vec<usize> = display.columns().map(|column| column.len()).collect();
+        assert_eq!(lengths, vec![ 4, 4, 2 ]);
+        assert_eq!(display.to_string(), "0 4 8\n1 5 9\n2 6\n3 7\n");
+    }
+
+    #[test]
+    fn validate_widths() {
+        let mut grid = Grid::new(GridOptions::default());
+        grid.add(Cell::from("one"));
+        grid.add(Cell::from("日本"));
+        assert_eq!(grid.validate(), Ok(()));
+
+        grid.add(Cell { contents: "three".into(), width: 2, alignment: Alignment::Left, flexible: false, colspan: 1, leader: None });
+        grid.add(Cell::from("four"));
+        grid.add(Cell { contents: "five".into(), width: 9, alignment: Alignment::Left, flexible: false, colspan: 1, leader: None });
+        assert_eq!(grid.validate(), Err(vec![ 2, 4 ]));
+    }
+
+    #[test]
+    fn validate_escape_codes() {
+        let mut grid = Grid::new(GridOptions::default());
+        grid.add(Cell { contents: "\x1b[31mred\x1b[0m".into(), width: 3, alignment: Alignment::Left, flexible: false, colspan: 1, leader: None });
+        grid.add(Cell { contents: "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\".into(), width: 4, alignment: Alignment::Left, flexible: false, colspan: 1, leader: None });
+        assert_eq!(grid.validate(), Ok(()));
+
+        // The width still has to match the text that’s shown.
+        grid.add(Cell { contents: "\x1b[1mbold\x1b[0m".into(), width: 12, alignment: Alignment::Left, flexible: false, colspan: 1, leader: None });
+        assert_eq!(grid.validate(), Err(vec![ 2 ]));
+    }
+
+    #[test]
+    fn indentation() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            indent:     4,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(4);
+        assert_eq!(display.width(), 4 + 22);
+
+        let bits = "    one  two three  four\n    five six seven  eight\n    nine ten eleven twelve\n";
+        assert_eq!(display.to_string(), bits);
+    }
+
+    #[test]
+    #[cfg(feature = "terminal")]
+    fn terminal_width_fallback() {
+        assert_eq!(terminal_width(Some(132)), 132);
+        assert_eq!(terminal_width(None), 80);
+
+        let mut grid = Grid::new(GridOptions::default());
+        grid.add(Cell::from("one"));
+        grid.add(Cell::from("two"));
+
+        // Tests don’t usually run attached to a terminal, but either way
+        // two short cells fit into one line.
+        assert_eq!(grid.fit_into_terminal().unwrap().row_count(), 1);
+    }
+
+    #[test]
+    fn flexible_cell_allows_more_columns() {
+        let cells = ["one", "two", "three", "four", "five", "six",
+                     "a rather long name here", "eight", "nine", "ten"];
+
+        let mut rigid_grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        let mut flexible_grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &cells {
+            rigid_grid.add(Cell::from(*s));
+
+            let mut cell = Cell::from(*s);
+            cell.flexible = s.len() > 10;
+            flexible_grid.add(cell);
+        }
+
+        let rigid = rigid_grid.fit_into_width(39).unwrap();
+        assert_eq!(rigid.dimensions.widths.len(), 4);
+
+        let flexible = flexible_grid.fit_into_width(39).unwrap();
+        assert_eq!(flexible.dimensions.widths.len(), 5);
+        assert!(flexible.width() <= 39);
+        assert_eq!(flexible.to_string(), "one two                 three four five\nsix a rather long name… eight nine ten\n");
+    }
+
+    #[test]
+    fn parse_fillings() {
+        assert_eq!("spaces:2".parse(), Ok(Filling::Spaces(2)));
+        assert_eq!("3".parse(), Ok(Filling::Spaces(3)));
+        assert_eq!("text:|".parse(), Ok(Filling::Text("|".into())));
+        assert_eq!("text: | ".parse(), Ok(Filling::Text(" | ".into())));
+        assert_eq!(Filling::try_from("text:"), Ok(Filling::Text("".into())));
+    }
+
+    #[test]
+    fn parse_filling_errors() {
+        assert_eq!("spaces:two".parse::<Filling>(), Err(ParseError::InvalidNumber("two".into())));
+        assert_eq!("tabs:1".parse::<Filling>(), Err(ParseError::Unrecognised("tabs:1".into())));
+        assert_eq!(Filling::try_from("text:\n"), Err(ParseError::InvalidFilling(FillingError::ControlCharacter('\n'))));
+    }
+
+    #[test]
+    fn parse_directions() {
+        for s in &["ltr", "LeftToRight", "ACROSS"] {
+            assert_eq!(s.parse(), Ok(Direction::LeftToRight));
+        }
+
+        for s in &["ttb", "topToBottom", "Down"] {
+            assert_eq!(s.parse(), Ok(Direction::TopToBottom));
+        }
+
+        assert_eq!("sideways".parse::<Direction>(), Err(ParseError::Unrecognised("sideways".into())));
+    }
+
+    #[test]
+    fn parse_alignments() {
+        assert_eq!("left".parse(), Ok(Alignment::Left));
+        assert_eq!("Right".parse(), Ok(Alignment::Right));
+        assert_eq!("RightZeroPad".parse(), Ok(Alignment::RightZeroPad));
+
+        let error = "middle".parse::<Alignment>().unwrap_err();
+        assert_eq!(error, ParseError::Unrecognised("middle".into()));
+        assert_eq!(error.to_string(), "unrecognised value \"middle\"");
+    }
+
+    #[test]
+    fn markdown_table() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["name", "size", "a|b", "12", "c"] {
+            let mut cell = Cell::from(*s);
+            if s.chars().all(|c| c.is_ascii_digit()) || *s == "size" {
+                cell.alignment = Alignment::Right;
+            }
+            grid.add(cell);
+        }
+
+        let bits = "| name | size |\n| :--- | ---: |\n| a\\|b | 12 |\n| c |  |\n";
+        assert_eq!(grid.fit_into_columns(2).to_markdown(), bits);
+    }
+
+    #[test]
+    fn wasted_space() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_width(24).unwrap().wasted_space(), 6);
+        assert_eq!(grid.fit_into_columns(1).wasted_space(), 6 * 12 - 51);
+    }
+
+    #[test]
+    fn projected_columns() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "bb", "ccc", "dddd", "e", "ff", "g"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        let projected = display.project_columns(&[ 0, 2, 7 ]);
+
+        assert_eq!(projected.to_string(), "a    ccc\ndddd ff\ng\n");
+        assert_eq!(projected.width(), 4 + 1 + 3);
+
+        let reversed = projected.project_columns(&[ 1, 0 ]);
+        assert_eq!(reversed.to_string(), "ccc a\nff  dddd\n    g\n");
+    }
+
+    #[test]
+    fn aligned_on_separator() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["a: 1", "bb: 2", "ccc: 3", "none"] {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::On(":".into());
+            grid.add(cell);
+        }
+
+        let display = grid.fit_into_columns(1);
+        assert_eq!(display.to_string(), "   a: 1\n  bb: 2\n ccc: 3\nnone\n");
+        assert_eq!(display.width(), 7);
+    }
+
+    #[test]
+    fn aligned_on_longer_separator() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        // The whole separator has to match, so the dashes and arrows
+        // without spaces around them don’t count.
+        for s in &["x -> 1", "long-name -> 2", "a->b -> 3"] {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::On(" -> ".into());
+            grid.add(cell);
+        }
+
+        let display = grid.fit_into_columns(1);
+        assert_eq!(display.to_string(), "        x -> 1\nlong-name -> 2\n     a->b -> 3\n");
+        assert_eq!(display.width(), 14);
+    }
+
+    #[test]
+    fn owned_display() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven", "eight"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let borrowed = grid.fit_into_width(21).unwrap().to_string();
+        let owned = grid.into_display(21).unwrap();
+
+        assert_eq!(owned.to_string(), borrowed);
+        assert_eq!(owned.display().to_string(), borrowed);
+        assert_eq!(owned.into_grid().fit_into_width(21).unwrap().to_string(), borrowed);
+    }
+
+    #[test]
+    fn spanning_header() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        let mut header = Cell::from("Section header");
+        header.colspan = 2;
+        grid.add(header);
+        grid.add(Cell::from("x"));
+
+        for s in &["one", "two", "three", "four", "five", "six"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.row_count(), 3);
+        assert_eq!(display.to_string(), "Section header x\none     two    three\nfour    five   six\n");
+        assert!(display.last_row_is_full());
+    }
+
+    #[test]
+    fn spanning_cell_moves_to_next_row() {
+        let mut grid = Grid::new(GridOptions::default());
+
+        grid.add(Cell::from("a"));
+        grid.add(Cell::from("b"));
+        let mut wide = Cell::from("wide");
+        wide.colspan = 2;
+        grid.add(wide);
+
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.to_string(), "a  b\nwide\n");
+    }
+
+    #[test]
+    fn wide_text_filling_finds_fewest_lines() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::text(" | ").unwrap(),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["aaaaaaa", "bb", "cc", "d", "e", "fffffff", "gggg", "hhhhhh"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // Three columns fit into 26, but five columns fit into 31, and
+        // take up one line fewer.
+        assert_eq!(grid.fit_into_columns(3).width(), 26);
+        assert_eq!(grid.fit_into_columns(5).width(), 31);
+        assert_eq!(grid.fit_into_width(32).unwrap().row_count(), 2);
+
+        for maximum_width in 7 .. 60 {
+            // Try every number of columns to find the fewest lines.
+            let best = (1 ..= grid.cell_count)
+                .map(|num_columns| grid.fit_into_columns(num_columns))
+                .filter(|display| display.width() <= maximum_width)
+                .map(|display| display.row_count())
+                .min();
+
+            let display = grid.fit_into_width(maximum_width).unwrap();
+            assert_eq!(Some(display.row_count()), best, "width {}", maximum_width);
+        }
+    }
+
+    #[test]
+    fn wide_text_filling_exact_fit() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::text("<->").unwrap(),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // Two columns take up exactly 5 + 3 + 4 = 12.
+        assert_eq!(grid.fit_into_width(12).unwrap().to_string(), "one  <->two\nthree<->four\n");
+        assert_eq!(grid.fit_into_width(11).unwrap().row_count(), 4);
+        assert_eq!(grid.fit_into_width(4), None);
+    }
+
+    #[test]
+    fn header_widens_columns() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            filling:    Filling::Spaces(2),
+            header:     Some(vec![ "Name".into(), "Description of it".into() ]),
+            ..GridOptions::default()
+        });
+
+        for s in &["ls", "lists", "cp", "copies"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.width(), 4 + 2 + 17);
+        assert_eq!(display.to_string(), "Name  Description of it\nls    lists\ncp    copies\n");
+        assert_eq!(display.to_markdown(), "| Name | Description of it |\n| :--- | :--- |\n| ls | lists |\n| cp | copies |\n");
+    }
+
+    #[test]
+    fn header_counts_towards_fitting() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            header:     Some(vec![ "A very long label".into() ]),
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c", "d"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // Without the label, all four cells would fit on one line.
+        let display = grid.fit_into_width(20).unwrap();
+        assert_eq!(display.to_string(), "A very long label\na                 b\nc                 d\n");
+    }
+
+    #[test]
+    fn estimated_bytes_grows() {
+        let mut grid = Grid::new(GridOptions::default());
+        grid.add(Cell::from("short"));
+        let before = grid.estimated_bytes();
+
+        grid.add(Cell::from("a".repeat(1000)));
+        assert!(grid.estimated_bytes() >= before + 1000);
+    }
+
+    #[test]
+    fn fixed_height() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_rows(4);
+        assert_eq!(display.row_count(), 4);
+        assert_eq!(display.columns().count(), 3);
+        assert_eq!(display.to_string(), "1 5 9\n2 6 10\n3 7\n4 8\n");
+
+        assert_eq!(grid.fit_into_rows(0).row_count(), 1);
+        assert_eq!(grid.fit_into_rows(20).row_count(), 10);
+        assert_eq!(Grid::new(GridOptions::default()).fit_into_rows(4).to_string(), "");
+    }
+
+    #[test]
+    fn dotted_leaders() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for &(title, page) in &[("Chapter 1", "1"), ("Chapter 10", "45"), ("Appendix of terms", "102")] {
+            let mut cell = Cell::from(title);
+            cell.leader = Some('.');
+            grid.add(cell);
+
+            let mut cell = Cell::from(page);
+            cell.alignment = Alignment::Right;
+            grid.add(cell);
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.to_string(), "Chapter 1........   1\nChapter 10.......  45\nAppendix of terms 102\n");
+    }
+
+    #[test]
+    fn merged_grids() {
+        let mut whole = Grid::new(GridOptions::default());
+        let mut first = Grid::new(GridOptions::default());
+        let mut second = Grid::new(GridOptions {
+            filling:    Filling::Spaces(5),
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three"] {
+            whole.add(Cell::from(*s));
+            first.add(Cell::from(*s));
+        }
+
+        for s in &["four", "a much longer one"] {
+            whole.add(Cell::from(*s));
+            second.add(Cell::from(*s));
+        }
+
+        first.merge(second);
+        assert_eq!(first, whole);
+        assert_eq!(first.fit_into_width(30).unwrap().to_string(), whole.fit_into_width(30).unwrap().to_string());
+    }
+
+    #[test]
+    fn stretched_to_width() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let mut cell = Cell::from("4");
+        cell.alignment = Alignment::Right;
+        grid.add(cell);
+
+        let display = grid.fit_into_width_stretched(20).unwrap();
+        assert_eq!(display.width(), 20);
+        assert_eq!(display.to_string(), "one   two  three   4\n");
+    }
+
+    #[test]
+    fn min_width_for_columns() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(2),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven"] {
+            grid.add(Cell::from(*s));
+        }
+
+        for num_columns in 1 .. 8 {
+            assert_eq!(grid.min_width_for_columns(num_columns), grid.fit_into_columns(num_columns).width());
+        }
+
+        assert_eq!(grid.min_width_for_columns(2), 5 + 2 + 5);
+    }
+
+    #[test]
+    fn page_breaks() {
+        let mut grid = Grid::new(GridOptions {
+            direction:         Direction::LeftToRight,
+            page_break_every:  Some(2),
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_columns(2).to_string(), "a b\nc d\n\ne f\ng h\n\ni j\n");
+        assert_eq!(grid.fit_into_columns(5).to_string(), "a b c d e\nf g h i j\n");
+    }
+
+    #[test]
+    fn padded_cells() {
+        let cell = Cell::from("hi").padded_to(5, Alignment::Right);
+        assert_eq!(cell.contents, "   hi");
+        assert_eq!(cell.width, 5);
+
+        let cell = Cell::from("hi").padded_to(4, Alignment::Left);
+        assert_eq!(cell.contents, "hi  ");
+
+        let cell = Cell::from("hello").padded_to(3, Alignment::Right);
+        assert_eq!(cell.contents, "hello");
+        assert_eq!(cell.width, 5);
+    }
+
+    #[test]
+    fn enumerated_cells() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        let triples: Vec<(usize, usize, &str)> = display.enumerate_cells()
+                                                       .map(|(y, x, cell)| (y, x, &cell.contents[..]))
+                                                       .collect();
+
+        assert_eq!(triples.len(), 7);
+        assert_eq!(triples[0], (0, 0, "one"));
+        assert_eq!(triples[2], (0, 2, "seven"));
+        assert!(triples.contains(&(2, 1, "six")));
+        assert_eq!(triples.last(), Some(&(2, 1, "six")));
+    }
+
+    #[test]
+    fn centred_with_extra_on_the_right() {
+        let mut grid = Grid::new(GridOptions {
+            direction:         Direction::TopToBottom,
+            pad_final_column:  true,
+            ..GridOptions::default()
+        });
+
+        for s in &["abcdef", "abc"] {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::Center { extra_on: Side::Right };
+            grid.add(cell);
+        }
+
+        assert_eq!(grid.fit_into_columns(1).to_string(), "abcdef\n abc  \n");
+    }
+
+    #[test]
+    fn centred_with_extra_on_the_left() {
+        let mut grid = Grid::new(GridOptions {
+            direction:         Direction::TopToBottom,
+            pad_final_column:  true,
+            ..GridOptions::default()
+        });
+
+        for s in &["abcdef", "abc"] {
+            let mut cell = Cell::from(*s);
+            cell.alignment = Alignment::Center { extra_on: Side::Left };
+            grid.add(cell);
+        }
+
+        assert_eq!(grid.fit_into_columns(1).to_string(), "abcdef\n  abc \n");
+        assert_eq!("Center".parse(), Ok(Alignment::Center { extra_on: Side::Right }));
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut grid = Grid::with_capacity(GridOptions::default(), 10);
+        let capacity = grid.capacity();
+        assert!(capacity >= 10);
+
+        for _ in 0 .. 10 {
+            grid.add(Cell::from("cell"));
+        }
+
+        assert_eq!(grid.capacity(), capacity);
+    }
+
+    #[test]
+    fn render_to_fmt() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        let mut output = String::from("> ");
+        display.render_to_fmt(&mut output).unwrap();
+
+        assert_eq!(output, format!("> {}", display));
+    }
+
+    #[test]
+    fn tie_break_min_waste() {
+        let build = |tie_break| {
+            let mut grid = Grid::new(GridOptions {
+                direction:  Direction::TopToBottom,
+                tie_break,
+                ..GridOptions::default()
+            });
+
+            for s in &["ab", "cde", "fghij", "kl"] {
+                grid.add(Cell::from(*s));
+            }
+
+            grid
+        };
+
+        let most_columns = build(TieBreak::MaxColumns);
+        let display = most_columns.fit_into_width(8).unwrap();
+        assert_eq!(display.to_string(), "ab    kl\ncde\nfghij\n");
+        assert_eq!(display.wasted_space(), 9);
+
+        let least_waste = build(TieBreak::MinWaste);
+        let display = least_waste.fit_into_width(8).unwrap();
+        assert_eq!(display.to_string(), "ab\ncde\nfghij\nkl\n");
+        assert_eq!(display.wasted_space(), 8);
+    }
+
+    #[test]
+    fn footer_aligned_to_columns() {
+        let mut total = Cell::from("15");
+        total.alignment = Alignment::Right;
+
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            footer:     Some(vec![ Cell::from("total"), total ]),
+            ..GridOptions::default()
+        });
+
+        for &(name, count) in &[("apples", "3"), ("pears", "12")] {
+            grid.add(Cell::from(name));
+
+            let mut cell = Cell::from(count);
+            cell.alignment = Alignment::Right;
+            grid.add(cell);
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.to_string(), "apples  3\npears  12\ntotal  15\n");
+        assert_eq!(display.to_markdown(), "| apples | 3 |\n| :--- | ---: |\n| pears | 12 |\n| total | 15 |\n");
+    }
+
+    #[test]
+    fn footer_widens_columns() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            footer:     Some(vec![ Cell::from("a long footer") ]),
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c", "d"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.width(), 13 + 1 + 1);
+        assert_eq!(display.to_string(), "a             b\nc             d\na long footer\n");
+    }
+
+    #[test]
+    fn column_and_filling_widths() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::text(" | ").unwrap(),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.width_of_column(0), Some(5));
+        assert_eq!(display.width_of_column(1), Some(5));
+        assert_eq!(display.width_of_column(2), Some(6));
+        assert_eq!(display.width_of_column(3), None);
+        assert_eq!(display.filling_width(), 3);
+        assert_eq!(display.width(), 5 + 5 + 6 + 3 * 2);
+    }
+
+    #[test]
+    fn from_rectangular_rows() {
+        let rows = vec![ vec![ "a", "bb", "c" ],
+                         vec![ "dd", "e", "ff" ] ];
+
+        let (grid, num_columns) = Grid::from_rows(GridOptions::default(), rows);
+        assert_eq!(num_columns, 3);
+        assert_eq!(grid.fit_into_columns(num_columns).to_string(), "a  bb c\ndd e  ff\n");
+    }
+
+    #[test]
+    fn from_ragged_rows() {
+        let options = GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        };
+
+        let rows = vec![ vec![ String::from("one") ],
+                         vec![ String::from("two"), String::from("three") ],
+                         vec![ String::from("four") ] ];
+
+        // The short rows are padded out with empty cells, which still get
+        // their fillings before them.
+        let (grid, num_columns) = Grid::from_rows(options, rows);
+        assert_eq!(num_columns, 2);
+        assert_eq!(grid.fit_into_columns(num_columns).to_string(), "one  \ntwo  three\nfour \n");
+        assert_eq!(grid.cell_count, 6);
+    }
+
+    #[test]
+    fn empty_cells_end_lines() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "", ""] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_columns(3).to_string(), "a||\n");
+    }
+
+    #[test]
+    fn no_empty_trailing_columns() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::text("|").unwrap(),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(10);
+        assert_eq!(display.to_string(), "a|b|c\n");
+        assert_eq!(display.width(), 5);
+        assert!(display.is_complete());
+    }
+
+    #[test]
+    fn no_empty_trailing_columns_downwards() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // Two lines are enough for ten cells in nine columns, which only
+        // fills five of them.
+        let display = grid.fit_into_columns(9);
+        assert_eq!(display.columns().count(), 5);
+        assert_eq!(display.to_string(), "1 3 5 7 9\n2 4 6 8 10\n");
+    }
+
+    #[test]
+    fn wrapped_in_capped_column() {
+        let mut grid = Grid::new(GridOptions {
+            direction:          Direction::LeftToRight,
+            column_max_widths:  Some(vec![ 3, 12 ]),
+            wrap_mode:          WrapMode::Word,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "a short cell", "two", "a much longer cell", "six", "end"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.width_of_column(1), Some(12));
+        assert_eq!(display.row_count(), 3);
+        assert_eq!(display.to_string(), "one a short cell\ntwo a much\n    longer cell\nsix end\n");
+    }
+
+    #[test]
+    fn truncated_in_capped_column() {
+        let mut grid = Grid::new(GridOptions {
+            direction:          Direction::LeftToRight,
+            column_max_widths:  Some(vec![ 6 ]),
+            ..GridOptions::default()
+        });
+
+        for s in &["a long first cell", "b", "c", "d"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_columns(2).to_string(), "a lon… b\nc      d\n");
+    }
+
+    #[test]
+    fn wrapping_words() {
+        assert_eq!(wrap_words("the quick brown fox", 10), vec![ "the quick", "brown fox" ]);
+        assert_eq!(wrap_words("abcdefgh ij", 3), vec![ "abc", "def", "gh", "ij" ]);
+        assert_eq!(wrap_words("", 5), Vec::<String>::new());
+    }
+
+    #[test]
+    fn iterating_over_cells() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["one", "two", "three"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let mut visited = Vec::new();
+        for cell in &grid {
+            visited.push(&cell.contents[..]);
+        }
+        assert_eq!(visited, vec![ "one", "two", "three" ]);
+
+        let cells = grid.into_iter().collect::<Vec<_>>();
+        assert_eq!(cells, vec![ Cell::from("one"), Cell::from("two"), Cell::from("three") ]);
+    }
+
+    #[test]
+    fn minimal_area() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["long cell", "a", "long cell", "b"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // One row is 23 columns wide, but two rows of 11 take up less room.
+        let widest = grid.fit_into_width(30).unwrap();
+        assert_eq!((widest.width(), widest.row_count()), (23, 1));
+
+        let smallest = grid.fit_minimal_area(30).unwrap();
+        assert_eq!((smallest.width(), smallest.row_count()), (11, 2));
+        assert_eq!(smallest.to_string(), "long cell a\nlong cell b\n");
+
+        assert!(grid.fit_minimal_area(8).is_none());
+    }
+
+    #[test]
+    fn bordered() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let bordered = grid.fit_into_columns(3).to_bordered_string();
+        let lines: Vec<&str> = bordered.lines().collect();
+
+        assert_eq!(lines[0], "┌──────┬──────┬───────┐");
+        assert_eq!(lines[2], "├──────┼──────┼───────┤");
+        assert_eq!(lines[4], "└──────┴──────┴───────┘");
+
+        // The junctions line up with the borders between the cells.
+        for rule in &[lines[0], lines[2], lines[4]] {
+            let junctions: Vec<usize> = rule.chars().enumerate().filter(|&(_, c)| c != '─').map(|(i, _)| i).collect();
+            assert_eq!(junctions, vec![0, 7, 14, 22]);
+            assert_eq!(rule.chars().count(), 23);
+        }
+
+        assert_eq!(bordered, "┌──────┬──────┬───────┐\n\
+                              │ one  │ two  │ three │\n\
+                              ├──────┼──────┼───────┤\n\
+                              │ four │ five │       │\n\
+                              └──────┴──────┴───────┘\n");
+    }
+
+    #[test]
+    fn bordered_with_span() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        let mut title = Cell::from("title");
+        title.colspan = 2;
+        grid.add(title);
+        for s in &["ab", "cd"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let bits = "┌─────────┐\n\
+                    │ title   │\n\
+                    ├────┬────┤\n\
+                    │ ab │ cd │\n\
+                    └────┴────┘\n";
+        assert_eq!(grid.fit_into_columns(2).to_bordered_string(), bits);
+    }
+
+    #[test]
+    fn best_effort_with_over_wide_cell() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "far too wide", "b"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert!(grid.fit_into_width(8).is_none());
+
+        let display = grid.fit_into_width_best_effort(8);
+        assert_eq!(display.row_count(), 3);
+        assert_eq!(display.to_string(), "a\nfar too wide\nb\n");
+
+        // Layouts that fit are the same as with `fit_into_width`.
+        assert_eq!(grid.fit_into_width_best_effort(20), grid.fit_into_width(20).unwrap());
+    }
+
+    #[test]
+    fn uniform_cell_widths() {
+        let mut grid = Grid::new(GridOptions::default());
+        assert!(grid.cells_uniform_width());
+
+        for s in &["one", "two", "six"] {
+            grid.add(Cell::from(*s));
+        }
+        assert!(grid.cells_uniform_width());
+
+        grid.add(Cell::from("three"));
+        assert!(! grid.cells_uniform_width());
+
+        let mut other = Grid::new(GridOptions::default());
+        other.add(Cell::from("ten"));
+        other.merge(Grid::new(GridOptions::default()));
+        assert!(other.cells_uniform_width());
+
+        let mut narrower = Grid::new(GridOptions::default());
+        narrower.add(Cell::from("1"));
+        other.merge(narrower);
+        assert!(! other.cells_uniform_width());
+    }
+
+    #[test]
+    fn inserting_cells() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["one", "three"] {
+            grid.add(Cell::from(*s));
+        }
+
+        grid.insert(1, Cell::from("two"));
+        grid.insert(3, Cell::from("a very long four"));
+
+        let contents: Vec<&str> = grid.cells.iter().map(|cell| &cell.contents[..]).collect();
+        assert_eq!(contents, vec![ "one", "two", "three", "a very long four" ]);
+        assert_eq!(grid.cell_count, 4);
+        assert_eq!(grid.widest_cell_length, 16);
+        assert_eq!(grid.width_sum, 3 + 3 + 5 + 16);
+        assert_eq!(grid.fit_into_columns(4).to_string(), "one two three a very long four\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn inserting_past_the_end() {
+        let mut grid = Grid::new(GridOptions::default());
+        grid.insert(1, Cell::from("one"));
+    }
+
+    #[test]
+    fn removing_cells() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["one", "the widest", "three", "four"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.remove(1), Cell::from("the widest"));
+        assert_eq!(grid.cell_count, 3);
+        assert_eq!(grid.widest_cell_length, 5);
+        assert_eq!(grid.width_sum, 3 + 5 + 4);
+        assert_eq!(grid.fit_into_width(14).unwrap().to_string(), "one three four\n");
+
+        assert_eq!(grid.remove(0), Cell::from("one"));
+        assert_eq!(grid.narrowest_cell_length, 4);
+    }
+
+    #[test]
+    fn tab_separated_values() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve", "thirteen"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let tsv = "one\ttwo\tthree\tfour\n\
+                   five\tsix\tseven\teight\n\
+                   nine\tten\televen\ttwelve\n\
+                   thirteen\t\t\t\n";
+        assert_eq!(grid.fit_into_columns(4).to_tsv(), tsv);
+    }
+
+    #[test]
+    fn small_grid_renders_like_grid() {
+        let options = || GridOptions {
+            filling:    Filling::Text(" | ".into()),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        };
+
+        let small: SmallGrid<[Cell; 4]> = Grid::from_cells(options(), [
+            Cell::from("cpu"), Cell::from("12%"), Cell::from("memory"), Cell::from("1.5G"),
+        ]);
+
+        let mut grid = Grid::new(options());
+        for s in &["cpu", "12%", "memory", "1.5G"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(small.widest_cell_length, grid.widest_cell_length);
+        assert_eq!(small.width_sum, grid.width_sum);
+        assert_eq!(small.fit_into_columns(2).to_string(), grid.fit_into_columns(2).to_string());
+        assert_eq!(small.fit_into_width(12).unwrap().to_string(), grid.fit_into_width(12).unwrap().to_string());
+        assert_eq!(small.fit_into_columns(2).to_string(), "cpu | memory\n12% | 1.5G\n");
+    }
+
+    #[test]
+    fn column_of_cell() {
+        for &direction in &[ Direction::LeftToRight, Direction::TopToBottom ] {
+            let mut grid = Grid::new(GridOptions {
+                direction,
+                ..GridOptions::default()
+            });
+
+            for s in &["one", "two", "three", "four", "five", "six", "seven"] {
+                grid.add(Cell::from(*s));
+            }
+
+            let display = grid.fit_into_columns(3);
+            for index in 0 .. 7 {
+                let column = display.column_of(index).unwrap();
+                let cell = &grid.cells[index];
+                assert!(display.columns().nth(column).unwrap().contains(&cell));
+            }
+            assert_eq!(display.column_of(7), None);
+        }
+
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.column_of(2), Some(0));
+        assert_eq!(display.column_of(3), Some(1));
+        assert_eq!(display.column_of(6), Some(2));
+
+        let projected = display.project_columns(&[ 2, 0 ]);
+        assert_eq!(projected.column_of(6), Some(0));
+        assert_eq!(projected.column_of(0), Some(1));
+        assert_eq!(projected.column_of(4), None);
+    }
+
+    #[test]
+    fn mixed_alignments_keep_the_gap() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            filling:    Filling::Spaces(2),
+            ..GridOptions::default()
+        });
+
+        let right = |contents: &str| {
+            let mut cell = Cell::from(contents);
+            cell.alignment = Alignment::Right;
+            cell
+        };
+
+        for cell in vec![ Cell::from("name"), right("12"), Cell::from("a"), right("3"),
+                          Cell::from("longer"), right("456"), Cell::from("b"), right("7") ] {
+            grid.add(cell);
+        }
+
+        let display = grid.fit_into_columns(4);
+        assert_eq!(display.to_string(), "name     12  a  3\n\
+                                         longer  456  b  7\n");
+        assert_eq!(display.to_string_debug(), "name··¦¦·12¦¦a¦¦3\n\
+                                               longer¦¦456¦¦b¦¦7\n");
+    }
+
+    #[test]
+    fn char_count_width() {
+        let cell = Cell::from_char_count("café");
+        assert_eq!(cell.contents.len(), 5);
+        assert_eq!(cell.width, 4);
+        assert_eq!(cell, Cell::from("café"));
+
+        // Wide characters are only counted once.
+        assert_eq!(Cell::from_char_count("日本").width, 2);
+        assert_eq!(Cell::from("日本").width, 4);
+    }
+
+    #[test]
+    fn positioned_rows() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let positioned = grid.fit_into_columns(2).to_positioned_string(5, 10);
+        assert_eq!(positioned, "\x1b[5;10Hone   two\x1b[6;10Hthree four\x1b[7;10Hfive");
+    }
+
+    #[test]
+    fn gutters() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            indent:     1,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // Sixteen columns, less the gutters, only leaves room for two.
+        let display = grid.fit_into_width_with_gutter(16, 3, 2).unwrap();
+        assert_eq!(display.width(), 3 + 1 + 10);
+        assert_eq!(display.to_string(), "    one   two\n    three four\n");
+
+        assert_eq!(grid.fit_into_width_with_gutter(4, 3, 2), None);
+        assert_eq!(grid.fit_into_width_with_gutter(usize::max_value(), 3, usize::max_value()), None);
+    }
+
+    #[test]
+    fn shrinking_to_fit() {
+        let mut grid = Grid::with_capacity(GridOptions::default(), 1000);
+        grid.add(Cell::from("one"));
+        grid.add(Cell::from("two"));
+
+        grid.shrink_to_fit();
+        assert!(grid.capacity() < 1000);
+        assert!(grid.capacity() >= 2);
+    }
+
+    #[test]
+    fn flex_column() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["ls", "list files", "1", "cp", "copy files", "1"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_with_flex_column(20, 3, 1).unwrap();
+        assert_eq!(display.width(), 20);
+        assert_eq!(display.width_of_column(1), Some(15));
+        assert_eq!(display.to_string(), "ls list files      1\ncp copy files      1\n");
+
+        assert_eq!(grid.fit_with_flex_column(15, 3, 1).unwrap().width_of_column(1), Some(10));
+        assert_eq!(grid.fit_with_flex_column(14, 3, 1), None);
+        assert_eq!(grid.fit_with_flex_column(20, 3, 3), None);
+    }
+
+    #[test]
+    fn grouped_numbers() {
+        let cell = Cell::from_number(1_234_567, ',');
+        assert_eq!(cell.contents, "1,234,567");
+        assert_eq!(cell.width, 9);
+        assert_eq!(cell.alignment, Alignment::Right);
+
+        assert_eq!(Cell::from_number(-1_234, ',').contents, "-1,234");
+        assert_eq!(Cell::from_number(-123, ',').contents, "-123");
+        assert_eq!(Cell::from_number(0, ',').contents, "0");
+        assert_eq!(Cell::from_number(123_456, ' ').contents, "123 456");
+        assert_eq!(Cell::from_number(i64::min_value(), ',').contents, "-9,223,372,036,854,775,808");
+    }
+
+    #[test]
+    fn cached_widths() {
+        let mut cached = Grid::new(GridOptions::default());
+        let mut measured = Grid::new(GridOptions::default());
+
+        for s in &["main.rs", "lib.rs", "日本.rs", "main.rs", "日本.rs", "main.rs"] {
+            cached.add_cached(s);
+            measured.add(Cell::from(*s));
+        }
+
+        // Each string only gets measured once.
+        assert_eq!(cached.width_cache.len(), 3);
+        assert_eq!(cached.cells, measured.cells);
+        assert_eq!(cached.fit_into_width(30).unwrap().to_string(),
+                   measured.fit_into_width(30).unwrap().to_string());
+    }
+
+    #[test]
+    fn no_filling_after_last_cell() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_columns(3).to_string(), "one |two |three\nfour|five\n");
+    }
+
+    #[test]
+    fn no_filling_after_last_cell_going_down() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text(" | ".into()),
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        // The last column runs out a line early, so the last line stops
+        // after the first column, padding and all.
+        assert_eq!(grid.fit_into_columns(2).to_string(), "one   | four\ntwo   | five\nthree\n");
+    }
+
+    #[test]
+    fn truncation_sides() {
+        assert_eq!(truncate("abcdefgh", 5), ("abcd…".into(), 5));
+        assert_eq!(truncate_start("abcdefgh", 5, "…"), ("…efgh".into(), 5));
+        assert_eq!(truncate_both("abcdefgh", 5), ("…cde…".into(), 5));
+        assert_eq!(truncate_both("abcdefgh", 6), ("…cdef…".into(), 6));
+        assert_eq!(truncate_start("日本語", 4, "…"), ("…語".into(), 3));
+
+        let mut grid = Grid::new(GridOptions {
+            direction:          Direction::TopToBottom,
+            column_max_widths:  Some(vec![ 6 ]),
+            ..GridOptions::default()
+        });
+
+        for alignment in vec![ Alignment::Left, Alignment::Right, Alignment::Center { extra_on: Side::Right } ] {
+            grid.add(Cell::from("/usr/local/bin").padded_to(0, alignment));
+        }
+
+        assert_eq!(grid.fit_into_columns(1).to_string(), "/usr/…\n…l/bin\n…loca…\n");
+    }
+
+    #[test]
+    fn retaining_cells() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["a", "bb", "ccc", "dd", "eeeee", "ffff"] {
+            grid.add(Cell::from(*s));
+        }
+
+        grid.retain(|cell| cell.width > 2);
+
+        let contents: Vec<&str> = grid.cells.iter().map(|cell| &cell.contents[..]).collect();
+        assert_eq!(contents, vec![ "ccc", "eeeee", "ffff" ]);
+        assert_eq!(grid.cell_count, 3);
+        assert_eq!(grid.widest_cell_length, 5);
+        assert_eq!(grid.narrowest_cell_length, 3);
+        assert_eq!(grid.width_sum, 12);
+
+        grid.retain(|_| false);
+        assert_eq!((grid.cell_count, grid.widest_cell_length, grid.width_sum), (0, 0, 0));
+    }
+
+    #[test]
+    fn widths_of_lines() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            filling:    Filling::Spaces(2),
+            indent:     1,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.width(), 1 + 5 + 2 + 4);
+        assert_eq!(display.line_widths(), vec![ 1 + 5 + 2 + 3, 1 + 5 + 2 + 4, 1 + 4 ]);
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn normalized_cells() {
+        let decomposed = Cell::from_normalized("cafe\u{301}");
+        let precomposed = Cell::from_normalized("caf\u{e9}");
+        assert_eq!(decomposed, precomposed);
+        assert_eq!(decomposed.width, 4);
+
+        let mut grid = Grid::new(GridOptions::default());
+        grid.add(decomposed);
+        grid.add(precomposed);
+        assert_eq!(grid.fit_into_columns(2).to_string(), "caf\u{e9} caf\u{e9}\n");
+    }
+
+    #[test]
+    fn layout_changes() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            ..GridOptions::default()
+        });
+
+        for s in &["aaaa", "bb", "cc", "dddd", "e"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let previous = grid.fit_into_width(9).unwrap().dimensions().clone();
+        assert!(! grid.layout_changed_since(9, &previous));
+
+        // There’s a gap at the bottom of the second column for this one.
+        grid.add(Cell::from("f"));
+        assert!(! grid.layout_changed_since(9, &previous));
+
+        grid.add(Cell::from("gggggg"));
+        assert!(grid.layout_changed_since(9, &previous));
+        assert!(grid.layout_changed_since(5, &previous));
+    }
+
+    #[test]
+    fn inline() {
+        let mut grid = Grid::new(GridOptions::default());
+        assert_eq!(grid.to_inline(", "), "");
+
+        for s in &["one", "two", "three", "four"] {
+            grid.add(Cell::from(*s));
+        }
+        assert_eq!(grid.to_inline(", "), "one, two, three, four");
+    }
+
+    #[test]
+    fn more_columns_than_cells() {
+        let mut grid = Grid::new(GridOptions::default());
+        assert_eq!(grid.fit_into_columns(100).dimensions.widths, vec![ 0 ]);
+
+        for s in &["one", "two", "three"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(100);
+        assert_eq!(display.dimensions.widths, vec![ 3, 3, 5 ]);
+        assert_eq!(display.row_count(), 1);
+        assert_eq!(display.to_string(), "one two three\n");
+    }
+
+    #[test]
+    fn mapped_rendering() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.map_render(|contents, _, _| contents.to_uppercase()), "ONE   TWO\nTHREE FOUR\nFIVE\n");
+        assert_eq!(display.to_string(), "one   two\nthree four\nfive\n");
+
+        let positions = display.map_render(|contents, row, column| {
+            if (row, column) == (1, 1) { contents.to_uppercase() } else { contents.into() }
+        });
+        assert_eq!(positions, "one   two\nthree FOUR\nfive\n");
+    }
+
+    #[test]
+    fn leading_ellipsis() {
+        let mut grid = Grid::new(GridOptions {
+            direction:          Direction::TopToBottom,
+            column_max_widths:  Some(vec![ 10 ]),
+            truncation:         Truncation::LeadingEllipsis("…".into()),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("/home/user/src/deep/path/file.rs"));
+        grid.add(Cell::from("short.rs"));
+
+        assert_eq!(grid.fit_into_columns(1).to_string(), "…h/file.rs\nshort.rs\n");
+
+        let mut grid = Grid::new(GridOptions {
+            column_max_widths:  Some(vec![ 10 ]),
+            truncation:         Truncation::LeadingEllipsis("...".into()),
+            ..GridOptions::default()
+        });
+
+        grid.add(Cell::from("/home/user/src/deep/path/file.rs"));
+        assert_eq!(grid.fit_into_columns(1).to_string(), "...file.rs\n");
+    }
+
+    #[test]
+    fn swapping_options() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["one", "two", "three"] {
+            grid.add(Cell::from(*s));
+        }
+
+        assert_eq!(grid.fit_into_columns(3).to_string(), "one two three\n");
+
+        grid.set_options(GridOptions {
+            filling:  Filling::Text(" | ".into()),
+            ..GridOptions::default()
+        });
+        assert_eq!(grid.options().filling, Filling::Text(" | ".into()));
+        assert_eq!(grid.fit_into_columns(3).to_string(), "one | two | three\n");
+    }
+
+    #[test]
+    fn default_alignment() {
+        let mut grid = Grid::new(GridOptions {
+            direction:          Direction::TopToBottom,
+            default_alignment:  Alignment::Right,
+            ..GridOptions::default()
+        });
+
+        grid.add_str("1");
+        grid.add_str("22");
+        grid.add_cached("333");
+        grid.add(Cell::from("4"));
+
+        assert!(grid.cells[.. 3].iter().all(|cell| cell.alignment == Alignment::Right));
+        assert_eq!(grid.fit_into_columns(1).to_string(), "  1\n 22\n333\n4\n");
+    }
+
+    #[test]
+    fn total_width_saturates() {
+        let dimensions = Dimensions { num_lines: 1, widths: vec![ 1; 1000 ] };
+        assert_eq!(dimensions.total_width(Width::max_value() / 2), Width::max_value());
+        assert_eq!(Dimensions { num_lines: 0, widths: Vec::new() }.total_width(Width::max_value()), 0);
+
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(Width::max_value() / 3),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "b", "c", "d", "e"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(5);
+        assert_eq!(display.width(), Width::max_value());
+        assert_eq!(display.wasted_space(), 0);
+        assert_eq!(display.line_widths(), vec![ Width::max_value() ]);
+        assert_eq!(grid.min_width_for_columns(5), Width::max_value());
+    }
+
+    #[test]
+    fn strings_of_columns() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Spaces(1),
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five", "six", "seven",
+                   "eight", "nine", "ten", "eleven", "twelve", "thirteen"]
+        {
+            grid.add(Cell::from(*s));
+        }
+
+        let columns = grid.fit_into_columns(4).column_strings();
+        assert_eq!(columns, vec![ "one\nfive\nnine\nthirteen", "two\nsix\nten\n",
+                                  "three\nseven\neleven\n", "four\neight\ntwelve\n" ]);
+    }
+
+    #[test]
+    fn minimum_gap() {
+        let mut grid = Grid::new(GridOptions {
+            filling:    Filling::Text("|".into()),
+            direction:  Direction::LeftToRight,
+            min_gap:    3,
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_width(19).unwrap();
+        assert_eq!(display.filling_width(), 3);
+        assert_eq!(display.width(), 4 + 3 + 4 + 3 + 5);
+        assert_eq!(grid.fit_into_width(18).unwrap().row_count(), 3);
+        assert_eq!(display.to_string(), "one  | two  | three\nfour | five\n");
+
+        grid.set_options(GridOptions { min_gap: 4, filling: Filling::Text("|".into()), ..GridOptions::default() });
+        assert_eq!(grid.fit_into_columns(2).to_string(), "one   |  two\nthree |  four\nfive\n");
+    }
+
+    #[test]
+    fn previewing_rows() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            header:     Some(vec![ "Name".into(), "Size".into() ]),
+            ..GridOptions::default()
+        });
+
+        for s in &["a", "1", "b", "2", "c", "3"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.preview(2), "Name Size\na    1\nb    2\n...\n");
+        assert_eq!(display.preview(3), display.to_string());
+        assert_eq!(display.preview(0), "Name Size\n...\n");
+    }
+
+    #[test]
+    fn uniform_widths_match_measured_widths() {
+        for &direction in &[ Direction::LeftToRight, Direction::TopToBottom ] {
+            let mut grid = Grid::new(GridOptions { direction, ..GridOptions::default() });
+            for s in &["abc", "def", "ghi", "jkl", "mno"] {
+                grid.add(Cell::from(*s));
             }
 
-            // Early abort: if there are so many columns that the width of the
-            // *column separators* is bigger than the width of the screen, then
-            // don’t even try to tabulate it.
-            // This is actually a necessary check, because the width is stored as
-            // a usize, and making it go negative makes it huge instead, but it
-            // also serves as a speed-up.
-            let total_separator_width = (num_columns - 1) * self.options.filling.width();
-            if maximum_width < total_separator_width {
-                continue;
+            for num_columns in 1 ..= 5 {
+                let display = grid.fit_into_columns(num_columns);
+                assert!(display.dimensions().widths.iter().all(|&width| width == 3));
             }
+        }
 
-            // Remove the separator width from the available space.
-            let adjusted_width = maximum_width - total_separator_width;
-
-            let potential_dimensions = self.column_widths(num_lines, num_columns);
-            if potential_dimensions.widths.iter().sum::<Width>() < adjusted_width {
-                smallest_dimensions_yet = Some(potential_dimensions);
-            } else {
-                return smallest_dimensions_yet;
-            }
+        // Five cells written top to bottom over two lines leave the fourth
+        // column empty, so it mustn’t get the cells’ width.
+        let mut grid = Grid::new(GridOptions { direction: Direction::TopToBottom, ..GridOptions::default() });
+        for s in &["abc", "def", "ghi", "jkl", "mno"] {
+            grid.add(Cell::from(*s));
         }
 
-        None
+        assert_eq!(grid.column_widths(2, 4).widths, vec![ 3, 3, 3, 0 ]);
+        assert_eq!(grid.column_widths(2, 3).widths, vec![ 3, 3, 3 ]);
     }
-}
 
+    #[test]
+    fn plain_string_without_escapes() {
+        let mut grid = Grid::new(GridOptions {
+            direction:   Direction::LeftToRight,
+            row_styles:  Some(RowStyles {
+                even: ("\x1b[7m".into(), "\x1b[0m".into()),
+                odd:  (String::new(), String::new()),
+            }),
+            ..GridOptions::default()
+        });
 
-/// A displayable representation of a [`Grid`](struct.Grid.html).
-///
-/// This type implements `Display`, so you can get the textual version
-/// of the grid by calling `.to_string()`.
-#[derive(PartialEq, Debug)]
-pub struct Display<'grid> {
+        grid.add(Cell { contents: "\x1b[31mred\x1b[0m".into(), width: 3, ..Cell::from("") });
+        grid.add(Cell::from("two"));
+        grid.add(Cell::from_hyperlinked("docs", "https://docs.rs/"));
+        grid.add(Cell { contents: "\x1b[1;32mok\x1b[0m".into(), width: 2, ..Cell::from("") });
 
-    /// The grid to display.
-    grid: &'grid Grid,
+        let display = grid.fit_into_columns(2);
+        let plain = display.to_plain_string();
+        assert!(! plain.contains('\x1b'));
+        assert_eq!(plain, "red  two\ndocs ok\n");
+        assert!(display.to_string().contains('\x1b'));
+    }
 
-    /// The pre-computed column widths for this grid.
-    dimensions: Dimensions,
-}
+    #[test]
+    fn constrained_columns() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::LeftToRight,
+            ..GridOptions::default()
+        });
 
-impl Display<'_> {
+        for s in &["id", "description", "x", "7", "a longer line", "y"] {
+            grid.add(Cell::from(*s));
+        }
 
-    /// Returns how many columns this display takes up, based on the separator
-    /// width and the number and width of the columns.
-    pub fn width(&self) -> Width {
-        self.dimensions.total_width(self.grid.options.filling.width())
-    }
+        let display = grid.fit_into_columns_constrained(3, &[ 0, 0, 4 ], &[ Width::max_value(), 8 ]);
+        assert_eq!(display.dimensions().widths, vec![ 2, 8, 4 ]);
+        assert_eq!(display.to_string(), "id descrip… x\n7  a longe… y\n");
 
-    /// Returns how many rows this display takes up.
-    pub fn row_count(&self) -> usize {
-        self.dimensions.num_lines
+        // Where the bounds cross, the minimum wins.
+        let display = grid.fit_into_columns_constrained(3, &[ 5 ], &[ 3 ]);
+        assert_eq!(display.dimensions().widths[0], 5);
     }
 
-    /// Returns whether this display takes up as many columns as were allotted
-    /// to it.
-    ///
-    /// It’s possible to construct tables that don’t actually use up all the
-    /// columns that they could, such as when there are more columns than
-    /// cells! In this case, a column would have a width of zero. This just
-    /// checks for that.
-    pub fn is_complete(&self) -> bool {
-        self.dimensions.widths.iter().all(|&x| x > 0)
-    }
-}
+    #[test]
+    fn rendered_lines() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["one", "two", "three", "four", "five"] {
+            grid.add(Cell::from(*s));
+        }
 
-impl fmt::Display for Display<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        for y in 0 .. self.dimensions.num_lines {
-            for x in 0 .. self.dimensions.widths.len() {
-                let num = match self.grid.options.direction {
-                    Direction::LeftToRight  => y * self.dimensions.widths.len() + x,
-                    Direction::TopToBottom  => y + self.dimensions.num_lines * x,
-                };
+        let display = grid.fit_into_columns(2);
+        let lines = display.as_lines();
+        assert_eq!(lines.len(), display.row_count());
+        assert_eq!(lines, vec![ "one   two", "three four", "five" ]);
 
-                // Abandon a line mid-way through if that’s where the cells end
-                if num >= self.grid.cells.len() {
-                    continue;
-                }
+        let joined: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        assert_eq!(joined, display.to_string());
+    }
 
-                let cell = &self.grid.cells[num];
-                if x == self.dimensions.widths.len() - 1 {
-                    match cell.alignment {
-                        Alignment::Left => {
-                            // The final column doesn’t need to have trailing spaces,
-                            // as long as it’s left-aligned.
-                            write!(f, "{}", cell.contents)?;
-                        },
-                        Alignment::Right => {
-                            let extra_spaces = self.dimensions.widths[x] - cell.width;
-                            write!(f, "{}", pad_string(&cell.contents, extra_spaces, Alignment::Right))?;
-                        }
-                    }
-                }
-                else {
-                    assert!(self.dimensions.widths[x] >= cell.width);
-                    match (&self.grid.options.filling, cell.alignment) {
-                        (Filling::Spaces(n), Alignment::Left) => {
-                            let extra_spaces = self.dimensions.widths[x] - cell.width + n;
-                            write!(f, "{}", pad_string(&cell.contents, extra_spaces, cell.alignment))?;
-                        },
-                        (Filling::Spaces(n), Alignment::Right) => {
-                            let s = spaces(*n);
-                            let extra_spaces = self.dimensions.widths[x] - cell.width;
-                            write!(f, "{}{}", pad_string(&cell.contents, extra_spaces, cell.alignment), s)?;
-                        },
-                        (Filling::Text(ref t), _) => {
-                            let extra_spaces = self.dimensions.widths[x] - cell.width;
-                            write!(f, "{}{}", pad_string(&cell.contents, extra_spaces, cell.alignment), t)?;
-                        },
-                    }
-                }
-            }
+    #[test]
+    fn zero_width_cells_fit_side_by_side() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            filling:    Filling::Spaces(1),
+            ..GridOptions::default()
+        });
 
-            writeln!(f)?;
+        for s in &["", "alpha", "", "", "beta", "", "gamma", "", "", "delta"] {
+            grid.add(Cell::from(*s));
         }
 
-        Ok(())
+        // Every cell fits side by side in 28 columns: nineteen for the
+        // contents and nine for the separators.
+        assert_eq!(grid.fit_into_width(28).unwrap().row_count(), 1);
+
+        for width in 5 .. 40 {
+            let display = grid.fit_into_width(width).unwrap();
+            let best = (1 ..= 10).rev()
+                .map(|num_columns| grid.fit_into_columns(num_columns))
+                .filter(|display| display.width() <= width)
+                .map(|display| display.row_count())
+                .min();
+            assert_eq!(Some(display.row_count()), best, "width {}", width);
+        }
     }
-}
 
+    #[test]
+    fn rotated() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["ab", "c", "de", "fg"] {
+            grid.add(Cell::from(*s));
+        }
 
-/// Pad a string with the given number of spaces.
-fn spaces(length: usize) -> String {
-    repeat(" ").take(length).collect()
-}
+        // "ab c\nde fg\n" turned clockwise: the last line becomes the
+        // leftmost column.
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.to_rotated_string(), "da\neb\n\nfc\ng\n");
 
-/// Pad a string with the given alignment and number of spaces.
-///
-/// This doesn’t take the width the string *should* be, rather the number
-/// of spaces to add.
-fn pad_string(string: &str, padding: usize, alignment: Alignment) -> String {
-    if alignment == Alignment::Left {
-        format!("{}{}", string, spaces(padding))
+        let mut grid = Grid::new(GridOptions::default());
+        grid.add(Cell::from("日x"));
+        assert_eq!(grid.fit_into_columns(1).to_rotated_string(), "日\n\nx\n");
     }
-    else {
-        format!("{}{}", spaces(padding), string)
+
+    #[test]
+    fn fit_where_accepted() {
+        let mut grid = Grid::new(GridOptions::default());
+        for s in &["a", "bb", "a very long cell", "c", "dd", "e"] {
+            grid.add(Cell::from(*s));
+        }
+
+        let display = grid.fit_into_width_where(80, |_| true).unwrap();
+        assert_eq!(display.dimensions().widths().len(), 6);
+
+        // Any layout with the long cell in a column of its own has to be
+        // turned down, leaving the single column.
+        let narrow = grid.fit_into_width_where(80, |dimensions| dimensions.widths().iter().all(|&width| width <= 10));
+        assert!(narrow.is_none());
+
+        // Without the long cell, a limit on the columns’ combined width
+        // rules out the three-column layout, which is 5 wide.
+        grid.remove(2);
+        let display = grid.fit_into_width_where(80, |dimensions| dimensions.widths().iter().sum::<Width>() <= 4).unwrap();
+        assert_eq!(display.dimensions().widths(), &[ 1, 2 ]);
+        grid.insert(2, Cell::from("a very long cell"));
+
+        let display = grid.fit_into_width_where(80, |dimensions| dimensions.num_lines() >= 3).unwrap();
+        assert_eq!(display.dimensions().widths(), &[ 16, 2 ]);
+        assert_eq!(display.row_count(), 3);
+        assert!(grid.fit_into_width_where(10, |_| true).is_none());
     }
-}
 
+    #[test]
+    fn matching_output() {
+        let mut grid = Grid::new(GridOptions { pad_final_column: true, ..GridOptions::default() });
+        for s in &["one", "two", "three"] {
+            grid.add(Cell::from(*s));
+        }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.to_string(), "one   two\nthree \n");
+        assert!(display.matches("one   two\nthree\n"));
+        assert!(display.matches("one   two  \nthree"));
+        assert!(! display.matches("one two\nthree\n"));
+        assert!(! display.matches("one   two\n"));
+    }
 
     #[test]
-    fn no_items() {
-        let grid = Grid::new(GridOptions {
+    fn layout_summary() {
+        let mut grid = Grid::new(GridOptions {
             direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
+            filling:    Filling::Spaces(1),
+            ..GridOptions::default()
         });
 
-        let display = grid.fit_into_width(40).unwrap();
-
-        assert_eq!(display.dimensions.num_lines, 0);
-        assert!(display.dimensions.widths.is_empty());
+        for s in &["one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve"] {
+            grid.add(Cell::from(*s));
+        }
 
-        assert_eq!(display.width(), 0);
+        let display = grid.fit_into_columns(4);
+        let summary = display.summary();
+        assert!(summary.contains("4 cols × 3 rows"));
+        assert!(summary.contains("widths [5,4,5,6]"));
+        assert_eq!(summary, "Grid: 12 cells, 4 cols × 3 rows, widths [5,4,5,6], width 23");
     }
 
     #[test]
-    fn one_item() {
+    fn text_filling_padding_side() {
         let mut grid = Grid::new(GridOptions {
-            direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
+            filling:  Filling::Text("|".into()),
+            header:   Some(vec![ "x".into(), "y".into() ]),
+            ..GridOptions::default()
         });
 
-        grid.add(Cell::from("1"));
+        for s in &["a", "b", "ccc", "d"] {
+            grid.add(Cell::from(*s));
+        }
 
-        let display = grid.fit_into_width(40).unwrap();
+        assert_eq!(grid.fit_into_columns(2).to_string(), "x  |y\na  |b\nccc|d\n");
 
-        assert_eq!(display.dimensions.num_lines, 1);
-        assert_eq!(display.dimensions.widths, vec![ 1 ]);
+        grid.set_options(GridOptions {
+            filling:     Filling::Text("|".into()),
+            header:      Some(vec![ "x".into(), "y".into() ]),
+            text_align:  TextFillAlign::PadAfterSeparator,
+            ..GridOptions::default()
+        });
+        assert_eq!(grid.fit_into_columns(2).to_string(), "x|  y\na|  b\nccc|d\n");
 
-        assert_eq!(display.width(), 1);
+        // Right-aligned cells keep their padding in front of them.
+        grid.add(Cell { alignment: Alignment::Right, ..Cell::from("e") });
+        grid.add(Cell::from("f"));
+        assert_eq!(grid.fit_into_columns(2).to_string(), "x|  y\na|  b\nccc|d\n  e|f\n");
     }
 
     #[test]
-    fn one_item_exact_width() {
-        let mut grid = Grid::new(GridOptions {
-            direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
-        });
-
-        grid.add(Cell::from("1234567890"));
+    fn widest_cell_info() {
+        let mut grid = Grid::new(GridOptions::default());
+        assert_eq!(grid.widest_cell_info(), None);
 
-        let display = grid.fit_into_width(10).unwrap();
+        for s in &["one", "three", "eleven", "twelve", "two"] {
+            grid.add(Cell::from(*s));
+        }
 
-        assert_eq!(display.dimensions.num_lines, 1);
-        assert_eq!(display.dimensions.widths, vec![ 10 ]);
+        let (index, cell) = grid.widest_cell_info().unwrap();
+        assert_eq!(index, 2);
+        assert_eq!(cell.contents, "eleven");
 
-        assert_eq!(display.width(), 10);
+        grid.remove(2);
+        assert_eq!(grid.widest_cell_info().map(|(index, _)| index), Some(2));
     }
 
     #[test]
-    fn one_item_just_over() {
+    fn numbered_rows() {
         let mut grid = Grid::new(GridOptions {
-            direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
+            direction:    Direction::TopToBottom,
+            row_numbers:  true,
+            header:       Some(vec![ "A".into(), "B".into() ]),
+            ..GridOptions::default()
         });
 
-        grid.add(Cell::from("1234567890!"));
+        for i in 0 .. 24 {
+            grid.add(Cell::from(format!("c{}", i)));
+        }
 
-        assert_eq!(grid.fit_into_width(10), None);
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.row_count(), 12);
+        assert_eq!(display.width(), 3 + 3 + 1 + 3);
+
+        let lines = display.as_lines();
+        assert_eq!(lines[0], "   A   B");
+        assert_eq!(lines[1], " 1 c0  c12");
+        assert_eq!(lines[9], " 9 c8  c20");
+        assert_eq!(lines[12], "12 c11 c23");
     }
 
     #[test]
-    fn two_small_items() {
+    fn tab_aligned() {
         let mut grid = Grid::new(GridOptions {
-            direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
+            direction:  Direction::LeftToRight,
+            filling:    Filling::Text(" | ".into()),
+            tab_align:  Some(8),
+            ..GridOptions::default()
         });
 
-        grid.add(Cell::from("1"));
-        grid.add(Cell::from("2"));
+        for s in &["one", "a longer cell", "x", "four", "five", "y"] {
+            grid.add(Cell::from(*s));
+        }
 
-        let display = grid.fit_into_width(40).unwrap();
+        // With a tab stop every eight columns, the second column starts at
+        // column 8 and the third at 24, past the end of its longest cell.
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.to_string(), "one\ta longer cell\tx\nfour\tfive\t\ty\n");
 
-        assert_eq!(display.dimensions.num_lines, 1);
-        assert_eq!(display.dimensions.widths, vec![ 1, 1 ]);
+        // The indent moves the cells along, but the tab stops stay where
+        // they are, so the columns still start on them.
+        grid.set_options(GridOptions {
+            direction:  Direction::LeftToRight,
+            tab_align:  Some(4),
+            indent:     2,
+            ..GridOptions::default()
+        });
+        assert_eq!(grid.fit_into_columns(3).to_string(), "  one\ta longer cell\tx\n  four\tfive\t\t\ty\n");
+    }
 
-        assert_eq!(display.width(), 1 + 2 + 1);
+    #[test]
+    fn estimated_columns() {
+        let filling = Filling::Spaces(2);
+        let mut grid = Grid::new(GridOptions { filling: Filling::Spaces(2), ..GridOptions::default() });
+        for i in 0 .. 30 {
+            grid.add(Cell::from(format!("file{:02}.txt", i)));
+        }
+
+        // Every cell is ten wide, so the estimate is exact.
+        for &width in &[ 10, 21, 22, 40, 80, 400 ] {
+            let actual = grid.fit_into_width(width).unwrap().dimensions().widths().len();
+            assert_eq!(Grid::estimate_columns(30, 10, &filling, width), actual, "width {}", width);
+        }
+
+        // A narrower cell can only make room for more columns.
+        grid.add(Cell::from("a"));
+        let actual = grid.fit_into_width(40).unwrap().dimensions().widths().len();
+        assert!(Grid::estimate_columns(31, 10, &filling, 40) <= actual);
+
+        assert_eq!(Grid::estimate_columns(0, 10, &filling, 80), 0);
+        assert_eq!(Grid::estimate_columns(5, 90, &filling, 80), 0);
+        assert_eq!(Grid::estimate_columns(5, 0, &Filling::Spaces(0), 80), 5);
     }
 
     #[test]
-    fn two_medium_size_items() {
+    fn column_colors() {
         let mut grid = Grid::new(GridOptions {
-            direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
+            direction:      Direction::LeftToRight,
+            column_colors:  Some(vec![
+                (String::new(), String::new()),
+                ("\x1b[32m".into(), "\x1b[0m".into()),
+            ]),
+            ..GridOptions::default()
         });
 
-        grid.add(Cell::from("hello there"));
-        grid.add(Cell::from("how are you today?"));
+        for s in &["name", "size", "a", "1", "b", "22"] {
+            grid.add(Cell::from(*s));
+        }
 
-        let display = grid.fit_into_width(40).unwrap();
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.dimensions().widths(), &[ 4, 4 ]);
+        assert_eq!(display.to_string(), "name \x1b[32msize\x1b[0m\na    \x1b[32m1\x1b[0m\nb    \x1b[32m22\x1b[0m\n");
+        assert_eq!(display.to_plain_string(), "name size\na    1\nb    22\n");
+    }
 
-        assert_eq!(display.dimensions.num_lines, 1);
-        assert_eq!(display.dimensions.widths, vec![ 11, 18 ]);
+    #[test]
+    fn word_wrap_wide_characters_in_a_narrow_column() {
+        let mut grid = Grid::new(GridOptions {
+            column_max_widths:  Some(vec![ 1 ]),
+            wrap_mode:          WrapMode::Word,
+            ..GridOptions::default()
+        });
 
-        assert_eq!(display.width(), 11 + 2 + 18);
+        grid.add(Cell::from("日本"));
+        assert_eq!(grid.fit_into_columns(1).to_string(), "…\n…\n");
     }
 
     #[test]
-    fn two_big_items() {
+    fn word_wrap_escape_codes_in_a_capped_column() {
         let mut grid = Grid::new(GridOptions {
-            direction:  Direction::TopToBottom,
-            filling:    Filling::Spaces(2),
+            column_max_widths:  Some(vec![ 4 ]),
+            wrap_mode:          WrapMode::Word,
+            ..GridOptions::default()
         });
 
-        grid.add(Cell::from("nuihuneihsoenhisenouiuteinhdauisdonhuisudoiosadiuohnteihaosdinhteuieudi"));
-        grid.add(Cell::from("oudisnuthasuouneohbueobaugceoduhbsauglcobeuhnaeouosbubaoecgueoubeohubeo"));
+        grid.add(Cell::from("\x1b[31mred\x1b[0m"));
+        grid.add(Cell::from("abc"));
 
-        assert_eq!(grid.fit_into_width(40), None);
+        let display = grid.fit_into_columns(1);
+        for line in display.to_string().lines() {
+            assert!(chars_width(line) <= 4, "{:?}", line);
+        }
     }
 
     #[test]
-    fn that_example_from_earlier() {
+    fn byte_width_with_capped_columns() {
         let mut grid = Grid::new(GridOptions {
-            filling:    Filling::Spaces(1),
-            direction:  Direction::LeftToRight,
+            column_max_widths:  Some(vec![ 2 ]),
+            ..GridOptions::default()
         });
 
-        for s in &["one", "two", "three", "four", "five", "six", "seven",
-                   "eight", "nine", "ten", "eleven", "twelve"]
-        {
+        grid.add(Cell::from("abcdef"));
+        grid.add(Cell::from("x"));
+
+        let display = grid.fit_into_byte_width(40).unwrap();
+        assert!(display.to_string().lines().all(|line| line.len() <= 40));
+        assert_eq!(display.to_string(), "a… x\n");
+    }
+
+    #[test]
+    fn debug_rendering_keeps_the_layout() {
+        let mut grid = Grid::new(GridOptions {
+            indent:         2,
+            row_numbers:    true,
+            row_separator:  Some((1, "-".into())),
+            ..GridOptions::default()
+        });
+
+        for s in &["one", "two", "three", "four"] {
             grid.add(Cell::from(*s));
         }
 
-        let bits = "one  two three  four\nfive six seven  eight\nnine ten eleven twelve\n";
-        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
-        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+        let display = grid.fit_into_columns(2);
+        assert_eq!(display.to_string(), "  1 one   two\n    ----------\n  2 three four\n");
+        assert_eq!(display.to_string_debug(), "  1 one··¦two\n    ----------\n  2 three¦four\n");
     }
 
     #[test]
-    fn number_grid_with_pipe() {
+    fn equality_ignores_the_width_cache() {
+        let mut added = Grid::new(GridOptions::default());
+        let mut cached = Grid::new(GridOptions::default());
+        for s in &["one", "two", "one"] {
+            added.add_str(s);
+            cached.add_cached(s);
+        }
+
+        assert_eq!(added, cached);
+
+        let mut merged = Grid::new(GridOptions::default());
+        merged.merge(cached);
+        let mut plain = Grid::new(GridOptions::default());
+        plain.merge(added);
+        assert_eq!(merged, plain);
+    }
+
+    #[test]
+    fn line_widths_with_tabs() {
         let mut grid = Grid::new(GridOptions {
-            filling:    Filling::Text("|".into()),
             direction:  Direction::LeftToRight,
+            tab_align:  Some(8),
+            ..GridOptions::default()
         });
 
-        for s in &["one", "two", "three", "four", "five", "six", "seven",
-                   "eight", "nine", "ten", "eleven", "twelve"]
-        {
+        for s in &["one", "a longer cell", "x", "four", "five", "y", "z"] {
             grid.add(Cell::from(*s));
         }
 
-        let bits = "one |two|three |four\nfive|six|seven |eight\nnine|ten|eleven|twelve\n";
-        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
-        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+        // The third column starts at the tab stop at 24.
+        let display = grid.fit_into_columns(3);
+        assert_eq!(display.line_widths(), vec![ 25, 25, 1 ]);
+
+        let expanded: Vec<Width> = display.to_string().lines().map(|line| {
+            line.chars().fold(0, |width, c| if c == '\t' { (width / 8 + 1) * 8 } else { width + 1 })
+        }).collect();
+        assert_eq!(display.line_widths(), expanded);
     }
 
     #[test]
-    fn numbers_right() {
+    fn header_with_min_waste() {
         let mut grid = Grid::new(GridOptions {
-            filling:    Filling::Spaces(1),
-            direction:  Direction::LeftToRight,
+            direction:  Direction::TopToBottom,
+            header:     Some(vec![ "Name".into(), "Size".into() ]),
+            tie_break:  TieBreak::MinWaste,
+            ..GridOptions::default()
         });
 
-        for s in &["one", "two", "three", "four", "five", "six", "seven",
-                   "eight", "nine", "ten", "eleven", "twelve"]
-        {
-            let mut cell = Cell::from(*s);
-            cell.alignment = Alignment::Right;
-            grid.add(cell);
+        for i in 0 .. 100 {
+            grid.add(Cell::from(format!("{:04}", i)));
         }
 
-        let bits = " one two  three   four\nfive six  seven  eight\nnine ten eleven twelve\n";
-        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
-        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+        // Five columns of twenty cells each fill every row, so there’s no
+        // space wasted at all, even though eight columns would fit.
+        let display = grid.fit_into_width(40).unwrap();
+        assert_eq!(display.dimensions().widths().len(), 5);
+        assert_eq!(display.wasted_space(), 0);
+        assert!(display.width() <= 40);
     }
 
     #[test]
-    fn numbers_right_pipe() {
+    fn fit_into_rows_left_to_right() {
         let mut grid = Grid::new(GridOptions {
-            filling:    Filling::Text("|".into()),
             direction:  Direction::LeftToRight,
+            ..GridOptions::default()
         });
 
-        for s in &["one", "two", "three", "four", "five", "six", "seven",
-                   "eight", "nine", "ten", "eleven", "twelve"]
-        {
-            let mut cell = Cell::from(*s);
-            cell.alignment = Alignment::Right;
-            grid.add(cell);
+        for s in &["1", "2", "3", "4", "5", "6"] {
+            grid.add(Cell::from(*s));
         }
 
-        let bits = " one|two| three|  four\nfive|six| seven| eight\nnine|ten|eleven|twelve\n";
-        assert_eq!(grid.fit_into_width(24).unwrap().to_string(), bits);
-        assert_eq!(grid.fit_into_width(24).unwrap().row_count(), 3);
+        let display = grid.fit_into_rows(3);
+        assert_eq!(display.to_string(), "1 2\n3 4\n5 6\n");
+
+        // Two columns are needed for four rows, which only takes three
+        // when the cells go along the rows.
+        let display = grid.fit_into_rows(4);
+        assert_eq!(display.row_count(), 3);
+        assert_eq!(display.to_string(), "1 2\n3 4\n5 6\n");
     }
 
     #[test]
-    fn huge_separator() {
+    fn constrained_columns_keep_their_labels() {
         let mut grid = Grid::new(GridOptions {
-            filling:    Filling::Spaces(100),
             direction:  Direction::LeftToRight,
+            header:     Some(vec![ "Name".into(), "Description".into() ]),
+            ..GridOptions::default()
         });
 
-        grid.add("a".into());
-        grid.add("b".into());
+        for s in &["one", "the first number", "two", "the second number"] {
+            grid.add(Cell::from(*s));
+        }
 
-        assert_eq!(grid.fit_into_width(99), None);
+        let display = grid.fit_into_columns_constrained(2, &[], &[ 2, 5 ]);
+        assert_eq!(display.dimensions().widths(), &[ 4, 11 ]);
+        assert_eq!(display.to_string(), "Name Description\none  the first …\ntwo  the second…\n");
     }
 
     #[test]
-    fn huge_yet_unused_separator() {
+    fn constrained_columns_keep_their_footers() {
         let mut grid = Grid::new(GridOptions {
-            filling:    Filling::Spaces(100),
             direction:  Direction::LeftToRight,
+            footer:     Some(vec![ Cell::from("total"), Cell::from("two numbers") ]),
+            ..GridOptions::default()
         });
 
-        grid.add("abcd".into());
+        for s in &["one", "the first number", "two", "the second number"] {
+            grid.add(Cell::from(*s));
+        }
 
-        let display = grid.fit_into_width(99).unwrap();
+        let display = grid.fit_into_columns_constrained(2, &[], &[ 2, 5 ]);
+        assert_eq!(display.dimensions().widths(), &[ 5, 11 ]);
+        assert_eq!(display.to_string(), "one   the first …\ntwo   the second…\ntotal two numbers\n");
+    }
 
-        assert_eq!(display.dimensions.num_lines, 1);
-        assert_eq!(display.dimensions.widths, vec![ 4 ]);
+    #[test]
+    fn min_waste_finds_the_least_waste() {
+        let mut grid = Grid::new(GridOptions {
+            direction:  Direction::TopToBottom,
+            tie_break:  TieBreak::MinWaste,
+            ..GridOptions::default()
+        });
 
-        assert_eq!(display.width(), 4);
+        for &width in &[7, 4, 8, 3, 3, 2, 1, 6, 8] {
+            grid.add(Cell::from("x".repeat(width)));
+        }
+
+        // Five lines fit, wasting 38, but three lines waste only 15.
+        let display = grid.fit_into_width(22).unwrap();
+        assert_eq!(display.row_count(), 3);
+        assert_eq!(display.wasted_space(), 15);
+
+        for maximum_width in 8 .. 60 {
+            // Try every number of lines to find the least waste.
+            let least = (1 ..= grid.cell_count)
+                .map(|num_lines| grid.fit_into_rows(num_lines))
+                .filter(|display| display.width() <= maximum_width)
+                .map(|display| display.wasted_space())
+                .min();
+
+            let display = grid.fit_into_width(maximum_width).unwrap();
+            assert_eq!(Some(display.wasted_space()), least, "width {}", maximum_width);
+        }
     }
 }